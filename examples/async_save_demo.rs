@@ -0,0 +1,39 @@
+extern crate libharu;
+
+use libharu::prelude::*;
+
+/// Build a small document. The document is Send, so it can be moved into a task.
+fn build() -> anyhow::Result<Document> {
+    let doc = Document::new(|err| {
+        println!("err={:?}", err);
+    })?;
+
+    let font = doc.font("Helvetica", None)?;
+
+    for i in 1..=3 {
+        let page = doc.add_page()?;
+        let page = PageDescriptionMode::new(&page);
+        let height = page.height()?;
+
+        page.set_font_and_size(&font, 24.0)?;
+        page.run_text_mode(|page| {
+            page.text_out((50.0, height - 80.0), &format!("Page {}", i))
+        })?;
+    }
+
+    Ok(doc)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> anyhow::Result<()> {
+    let mut doc = build()?;
+
+    let task = tokio::spawn(async move {
+        let mut file = tokio::fs::File::create("async_save_demo.pdf").await?;
+        doc.save_to_async_writer(&mut file).await
+    });
+
+    task.await??;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+extern crate libharu;
+
+use libharu::prelude::*;
+
+fn main() -> anyhow::Result<()> {
+    let doc = Document::new(|err| {
+        println!("err={:?}", err);
+    })?;
+
+    doc.set_page_mode(PageMode::Outline)?;
+
+    let font = doc.font("Helvetica", None)?;
+    let mut toc = TableOfContents::new(&doc, &font, 12.0);
+
+    for chapter in 1..=3 {
+        for section in 0..=2 {
+            let page = doc.add_page()?;
+            let page = PageDescriptionMode::new(&page);
+            let height = page.height()?;
+
+            let (level, title) = match section {
+                0 => (0, format!("Chapter {}", chapter)),
+                _ => (1, format!("Section {}.{}", chapter, section)),
+            };
+
+            page.set_font_and_size(&font, 24.0)?;
+            page.run_text_mode(|page| {
+                page.text_out((50.0, height - 80.0), &title)
+            })?;
+
+            let dst = page.create_destination()?;
+            dst.set_xyz(0.0, height, 1.0)?;
+            toc.add_heading(level, &title, page.as_page(), &dst)?;
+        }
+    }
+
+    toc.finish()?;
+
+    doc.save_to_file("toc_demo.pdf")?;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+extern crate libharu;
+extern crate anyhow;
+
+use libharu::prelude::*;
+
+mod util;
+
+fn main() -> anyhow::Result<()> {
+    let doc = Document::new(|err| {
+        println!("err={:?}", err);
+    })?;
+
+    doc.set_compression_mode(CompressionMode::ALL)?;
+
+    let font = doc.font("Helvetica", None)?;
+
+    /* record the grid once */
+    let grid = doc.create_form_xobject(595.0, 842.0, |page| {
+        util::print_grid(&doc, page)
+    })?;
+
+    /* record a small logo once */
+    let logo = doc.create_form_xobject(100.0, 40.0, |page| {
+        page.set_rgb_fill((0.2, 0.3, 0.8))?;
+        page.run_path_mode(|page| {
+            page.rectangle((0.0, 0.0), 100.0, 40.0)?;
+            page.fill()
+        })?;
+
+        page.set_font_and_size(&font, 16.0)?;
+        page.set_gray_fill(1.0)?;
+        page.run_text_mode(|page| {
+            page.text_out((12.0, 14.0), "libharu")
+        })
+    })?;
+
+    for i in 0..4 {
+        let page = doc.add_page()?;
+        let page = PageDescriptionMode::new(&page);
+        let height = page.height()?;
+
+        /* place the templates instead of redrawing them */
+        page.execute_xobject(&grid)?;
+        page.as_page().execute_xobject_with_matrix(&logo, TransMatrix::translate(50.0, height - 90.0))?;
+
+        let angle = (i as Real) * std::f32::consts::FRAC_PI_8;
+        let matrix = TransMatrix::rotate(angle)
+            .then(&TransMatrix::scale(1.5, 1.5))
+            .then(&TransMatrix::translate(300.0, 400.0));
+        page.as_page().execute_xobject_with_matrix(&logo, matrix)?;
+
+        page.set_font_and_size(&font, 24.0)?;
+        page.run_text_mode(|page| {
+            page.text_out((50.0, height - 140.0), &format!("Page {}", i + 1))
+        })?;
+    }
+
+    doc.save_to_file("xobject_demo.pdf")?;
+
+    Ok(())
+}
@@ -0,0 +1,569 @@
+use crate::page::Page;
+use crate::conformance::{Conformance, ConformanceError};
+use crate::ffi;
+use crate::{Real, Color, CmykColor, Point, Rect};
+
+use std::ffi::CString;
+use sealed::Sealed;
+
+/// Highlight mode of link annotation.
+#[derive(Debug)]
+pub enum HighlightMode {
+    /// No highlighting.
+    NoHighlight,
+
+    /// Invert the contents of the annotation area.
+    InvertBox,
+
+    /// Invert the border of the annotation.
+    InvertBorder,
+
+    /// Display the annotation as if it were being pushed below the surface of the page.
+    DownAppearance,
+}
+
+/// Kind of text markup annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextMarkupKind {
+    /// Highlight the text.
+    Highlight,
+
+    /// Underline the text.
+    Underline,
+
+    /// Underline the text with a jagged line.
+    Squiggly,
+
+    /// Strike out the text.
+    StrikeOut,
+}
+
+/// Predefined icon of stamp annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampName {
+    /// Approved
+    Approved,
+
+    /// Experimental
+    Experimental,
+
+    /// NotApproved
+    NotApproved,
+
+    /// AsIs
+    AsIs,
+
+    /// Expired
+    Expired,
+
+    /// NotForPublicRelease
+    NotForPublicRelease,
+
+    /// Confidential
+    Confidential,
+
+    /// Final
+    Final,
+
+    /// Sold
+    Sold,
+
+    /// Departmental
+    Departmental,
+
+    /// ForComment
+    ForComment,
+
+    /// TopSecret
+    TopSecret,
+
+    /// Draft
+    Draft,
+
+    /// ForPublicRelease
+    ForPublicRelease,
+}
+
+/// Style of the ends of line annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// No line ending.
+    None,
+
+    /// Square filled with the interior color.
+    Square,
+
+    /// Circle filled with the interior color.
+    Circle,
+
+    /// Diamond filled with the interior color.
+    Diamond,
+
+    /// Two short lines meeting at the end of the line.
+    OpenArrow,
+
+    /// Triangle closed arrow filled with the interior color.
+    ClosedArrow,
+
+    /// Short line perpendicular to the line.
+    Butt,
+
+    /// Reversed open arrow.
+    ROpenArrow,
+
+    /// Reversed closed arrow.
+    RClosedArrow,
+
+    /// Short line slanted 30 degrees clockwise from perpendicular to the line.
+    Slash,
+}
+
+/// Quadrilateral of text marked by a text markup annotation. (e.g. a line of rotated text)
+#[derive(Debug, Clone)]
+pub struct Quad {
+    /// Top-left corner
+    pub top_left: Point,
+
+    /// Top-right corner
+    pub top_right: Point,
+
+    /// Bottom-left corner
+    pub bottom_left: Point,
+
+    /// Bottom-right corner
+    pub bottom_right: Point,
+}
+
+impl Copy for Quad {}
+
+impl From<Rect> for Quad {
+    fn from(rect: Rect) -> Self {
+        Self {
+            top_left: Point { x: rect.left, y: rect.top },
+            top_right: Point { x: rect.right, y: rect.top },
+            bottom_left: Point { x: rect.left, y: rect.bottom },
+            bottom_right: Point { x: rect.right, y: rect.bottom },
+        }
+    }
+}
+
+impl From<(Real, Real, Real, Real)> for Quad {
+    fn from(v: (Real, Real, Real, Real)) -> Self {
+        Rect::from(v).into()
+    }
+}
+
+pub(crate) mod sealed {
+    /// Annotation types of this crate, which keeps the handle out of the public interface.
+    pub trait Sealed {
+        /// Get internal handle.
+        fn handle(&self) -> libharu_sys::HPDF_Annotation;
+    }
+}
+
+/// Functions of all annotation types.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Annotation: Sealed {
+    /// Get the page which the annotation belongs to.
+    fn page(&self) -> &Page<'_>;
+
+    /// Set the text displayed for the annotation.
+    fn set_contents(&self, contents: &str) -> anyhow::Result<()> {
+        let key = CString::new("Contents")?;
+
+        let status = unsafe {
+            let contents = ffi::text_string_new(ffi::dict_mmgr(self.handle()), contents)?;
+            ffi::HPDF_Dict_Add(self.handle(), key.as_ptr(), contents)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Set the color of the annotation. (the background of the icon, the border, or the markup)
+    fn set_rgb_color<T>(&self, color: T) -> anyhow::Result<()>
+    where
+        T: Into<Color>
+    {
+        let color = color.into();
+
+        let status = unsafe {
+            libharu_sys::HPDF_Annot_SetRGBColor(self.handle(), libharu_sys::HPDF_RGBColor { r: color.red, g: color.green, b: color.blue })
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Annot_SetRGBColor failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Set the color of the annotation.
+    fn set_cmyk_color<T>(&self, color: T) -> anyhow::Result<()>
+    where
+        T: Into<CmykColor>
+    {
+        let color = color.into();
+        let color = libharu_sys::HPDF_CMYKColor { c: color.cyan, m: color.magenta, y: color.yellow, k: color.keyplate };
+
+        let status = unsafe {
+            libharu_sys::HPDF_Annot_SetCMYKColor(self.handle(), color)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Annot_SetCMYKColor failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+/// Functions of markup annotations, which carry a comment by an author.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait MarkupAnnotation: Annotation {
+    /// Set the author of the annotation.
+    fn set_author(&self, author: &str) -> anyhow::Result<()> {
+        let key = CString::new("T")?;
+
+        let status = unsafe {
+            let author = ffi::text_string_new(ffi::dict_mmgr(self.handle()), author)?;
+            ffi::HPDF_Dict_Add(self.handle(), key.as_ptr(), author)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Set the subject of the annotation.
+    fn set_subject(&self, subject: &str) -> anyhow::Result<()> {
+        let key = CString::new("Subj")?;
+
+        let status = unsafe {
+            let subject = ffi::text_string_new(ffi::dict_mmgr(self.handle()), subject)?;
+            ffi::HPDF_Dict_Add(self.handle(), key.as_ptr(), subject)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Set the opacity of the annotation. (0.0 ~ 1.0)
+    ///
+    /// PDF/A-1 does not allow transparency, so values below 1.0 are rejected with
+    /// `ConformanceError::Transparency` in PDF/A mode.
+    fn set_opacity(&self, opacity: Real) -> anyhow::Result<()> {
+        if !(0.0..=1.0).contains(&opacity) {
+            anyhow::bail!("opacity out of range ({})", opacity);
+        }
+
+        if opacity < 1.0 && self.page().doc().conformance() == Conformance::PdfA1B {
+            return Err(ConformanceError::Transparency.into());
+        }
+
+        let status = unsafe {
+            libharu_sys::HPDF_MarkupAnnot_SetTransparency(self.handle(), opacity)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_MarkupAnnot_SetTransparency failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+/// Replace an array entry of an annotation with the numbers.
+fn set_numbers(annot: libharu_sys::HPDF_Annotation, key: &str, values: &[Real]) -> anyhow::Result<()> {
+    let key = CString::new(key)?;
+
+    let status = unsafe {
+        let array = ffi::HPDF_Array_New(ffi::dict_mmgr(annot));
+        if array.is_null() {
+            anyhow::bail!("HPDF_Array_New failed");
+        }
+
+        let mut status = ffi::HPDF_Dict_Add(annot, key.as_ptr(), array);
+        for value in values {
+            if status != 0 {
+                break;
+            }
+            status = ffi::HPDF_Array_AddReal(array, *value);
+        }
+
+        status
+    };
+
+    if status != 0 {
+        anyhow::bail!("HPDF_Array_AddReal failed (status={})", status);
+    }
+
+    Ok(())
+}
+
+/// Get the smallest rect which contains all the points.
+pub(crate) fn bounding_rect(points: &[Point]) -> Rect {
+    let mut rect = Rect { left: Real::MAX, top: Real::MIN, right: Real::MIN, bottom: Real::MAX };
+
+    for p in points {
+        rect.left = rect.left.min(p.x);
+        rect.right = rect.right.max(p.x);
+        rect.bottom = rect.bottom.min(p.y);
+        rect.top = rect.top.max(p.y);
+    }
+
+    rect
+}
+
+/// Set the position of the annotation on the page.
+pub(crate) fn set_rect(annot: libharu_sys::HPDF_Annotation, rect: Rect) -> anyhow::Result<()> {
+    set_numbers(annot, "Rect", &[rect.left, rect.bottom, rect.right, rect.top])
+}
+
+macro_rules! annotation_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub struct $name<'a, 'b> {
+            annot: libharu_sys::HPDF_Annotation,
+            page: &'a Page<'b>,
+        }
+
+        impl<'a, 'b> $name<'a, 'b> {
+            pub(crate) fn new(page: &'a Page<'b>, annot: libharu_sys::HPDF_Annotation) -> Self {
+                Self { annot, page }
+            }
+        }
+
+        impl<'a, 'b> Sealed for $name<'a, 'b> {
+            #[inline]
+            fn handle(&self) -> libharu_sys::HPDF_Annotation {
+                self.page.doc().enter_memory();
+                self.annot
+            }
+        }
+
+        impl<'a, 'b> Annotation for $name<'a, 'b> {
+            fn page(&self) -> &Page<'_> {
+                self.page
+            }
+        }
+    };
+}
+
+annotation_type!(
+    /// Link annotation handle type.
+    LinkAnnotation
+);
+
+annotation_type!(
+    /// Text markup annotation handle type. (highlight, underline, squiggly and strike-out)
+    TextMarkupAnnotation
+);
+
+annotation_type!(
+    /// Free text annotation handle type.
+    FreeTextAnnotation
+);
+
+annotation_type!(
+    /// Line annotation handle type.
+    LineAnnotation
+);
+
+annotation_type!(
+    /// Square or circle annotation handle type.
+    ShapeAnnotation
+);
+
+annotation_type!(
+    /// Stamp annotation handle type.
+    StampAnnotation
+);
+
+annotation_type!(
+    /// Popup annotation handle type, which displays the comment of its parent annotation.
+    PopupAnnotation
+);
+
+annotation_type!(
+    /// 3D annotation handle type, which shows 3D artwork in the page.
+    Annotation3D
+);
+
+impl<'a, 'b> MarkupAnnotation for TextMarkupAnnotation<'a, 'b> {}
+impl<'a, 'b> MarkupAnnotation for FreeTextAnnotation<'a, 'b> {}
+impl<'a, 'b> MarkupAnnotation for LineAnnotation<'a, 'b> {}
+impl<'a, 'b> MarkupAnnotation for ShapeAnnotation<'a, 'b> {}
+impl<'a, 'b> MarkupAnnotation for StampAnnotation<'a, 'b> {}
+
+impl<'a, 'b> LinkAnnotation<'a, 'b> {
+    /// Define the appearance when a mouse clicked on a link annotation.
+    pub fn set_highlight_mode(&self, mode: HighlightMode) -> anyhow::Result<()> {
+        let mode = match mode {
+            HighlightMode::NoHighlight => libharu_sys::HPDF_AnnotHighlightMode::HPDF_ANNOT_NO_HIGHTLIGHT,
+            HighlightMode::InvertBox => libharu_sys::HPDF_AnnotHighlightMode::HPDF_ANNOT_INVERT_BOX,
+            HighlightMode::InvertBorder => libharu_sys::HPDF_AnnotHighlightMode::HPDF_ANNOT_INVERT_BORDER,
+            HighlightMode::DownAppearance => libharu_sys::HPDF_AnnotHighlightMode::HPDF_ANNOT_DOWN_APPEARANCE,
+        };
+
+        let status = unsafe {
+            libharu_sys::HPDF_LinkAnnot_SetHighlightMode(self.handle(), mode)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_LinkAnnot_SetHighlightMode failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Define the style of the annotation's border. (A width of 0 hides the border.)
+    pub fn set_border_style(&self, width: Real, dash_on: u16, dash_off: u16) -> anyhow::Result<()> {
+        let status = unsafe {
+            libharu_sys::HPDF_LinkAnnot_SetBorderStyle(self.handle(), width, dash_on, dash_off)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_LinkAnnot_SetBorderStyle failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, 'b> TextMarkupAnnotation<'a, 'b> {
+    /// Set the areas of the marked text, and resize the annotation to contain all of them.
+    pub fn set_quad_points(&self, quads: &[Quad]) -> anyhow::Result<()> {
+        if quads.is_empty() {
+            anyhow::bail!("no quad points");
+        }
+
+        let points: Vec<Point> = quads.iter()
+            .flat_map(|q| vec![q.top_left, q.top_right, q.bottom_left, q.bottom_right])
+            .collect();
+        let values: Vec<Real> = points.iter().flat_map(|p| vec![p.x, p.y]).collect();
+
+        set_numbers(self.handle(), "QuadPoints", &values)?;
+        set_rect(self.handle(), bounding_rect(&points))
+    }
+}
+
+impl<'a, 'b> FreeTextAnnotation<'a, 'b> {
+    /// Set the default style of the text. (CSS2 style string, e.g. "font: 12pt Helvetica")
+    pub fn set_default_style(&self, style: &str) -> anyhow::Result<()> {
+        let style = CString::new(style)?;
+
+        let status = unsafe {
+            libharu_sys::HPDF_FreeTextAnnot_SetDefaultStyle(self.handle(), style.as_ptr())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_FreeTextAnnot_SetDefaultStyle failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, 'b> LineAnnotation<'a, 'b> {
+    /// Set the start and end points of the line, and the style of its ends.
+    pub fn set_position<T1, T2>(&self, start: T1, start_style: LineEnding, end: T2, end_style: LineEnding) -> anyhow::Result<()>
+    where
+        T1: Into<Point>,
+        T2: Into<Point>,
+    {
+        let start = start.into();
+        let end = end.into();
+
+        let status = unsafe {
+            libharu_sys::HPDF_LineAnnot_SetPosition(
+                self.handle(),
+                libharu_sys::HPDF_Point { x: start.x, y: start.y },
+                line_ending(start_style),
+                libharu_sys::HPDF_Point { x: end.x, y: end.y },
+                line_ending(end_style))
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_LineAnnot_SetPosition failed (status={})", status);
+        }
+
+        /* libharu leaves the rect of line annotations empty */
+        set_rect(self.handle(), bounding_rect(&[start, end]))
+    }
+
+    /// Set the color which fills the line endings.
+    pub fn set_interior_rgb_color<T>(&self, color: T) -> anyhow::Result<()>
+    where
+        T: Into<Color>
+    {
+        set_interior_rgb_color(self.handle(), color.into())
+    }
+}
+
+impl<'a, 'b> ShapeAnnotation<'a, 'b> {
+    /// Set the color which fills the shape.
+    pub fn set_interior_rgb_color<T>(&self, color: T) -> anyhow::Result<()>
+    where
+        T: Into<Color>
+    {
+        set_interior_rgb_color(self.handle(), color.into())
+    }
+}
+
+impl<'a, 'b> PopupAnnotation<'a, 'b> {
+    /// Set whether the popup is initially displayed open.
+    pub fn set_opened(&self, opened: bool) -> anyhow::Result<()> {
+        let opened = match opened {
+            true => libharu_sys::HPDF_TRUE,
+            false => libharu_sys::HPDF_FALSE,
+        };
+
+        let status = unsafe {
+            libharu_sys::HPDF_PopupAnnot_SetOpened(self.handle(), opened)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_PopupAnnot_SetOpened failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+fn set_interior_rgb_color(annot: libharu_sys::HPDF_Annotation, color: Color) -> anyhow::Result<()> {
+    let status = unsafe {
+        libharu_sys::HPDF_MarkupAnnot_SetInteriorRGBColor(annot, libharu_sys::HPDF_RGBColor { r: color.red, g: color.green, b: color.blue })
+    };
+
+    if status != 0 {
+        anyhow::bail!("HPDF_MarkupAnnot_SetInteriorRGBColor failed (status={})", status);
+    }
+
+    Ok(())
+}
+
+fn line_ending(style: LineEnding) -> libharu_sys::HPDF_LineAnnotEndingStyle {
+    match style {
+        LineEnding::None => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_NONE,
+        LineEnding::Square => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_SQUARE,
+        LineEnding::Circle => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_CIRCLE,
+        LineEnding::Diamond => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_DIAMOND,
+        LineEnding::OpenArrow => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_OPENARROW,
+        LineEnding::ClosedArrow => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_CLOSEDARROW,
+        LineEnding::Butt => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_BUTT,
+        LineEnding::ROpenArrow => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_ROPENARROW,
+        LineEnding::RClosedArrow => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_RCLOSEDARROW,
+        LineEnding::Slash => libharu_sys::HPDF_LineAnnotEndingStyle::HPDF_LINE_ANNOT_SLASH,
+    }
+}
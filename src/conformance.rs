@@ -0,0 +1,46 @@
+/// Conformance level of the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conformance {
+    /// No additional restrictions.
+    None,
+
+    /// PDF/A-1b (ISO 19005-1, level B).
+    PdfA1B,
+}
+
+/// Error returned when an operation would break the conformance level of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConformanceError {
+    /// The font is not embedded in the document.
+    NonEmbeddedFont(String),
+
+    /// Encryption is not allowed.
+    Encryption,
+
+    /// Transparency is not allowed.
+    Transparency,
+
+    /// 3D artwork is not allowed.
+    Artwork3D,
+
+    /// Embedded files are not allowed.
+    EmbeddedFile,
+
+    /// The XMP metadata does not identify the conformance level.
+    MissingPdfaId,
+}
+
+impl std::fmt::Display for ConformanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConformanceError::NonEmbeddedFont(name) => write!(f, "font \"{}\" is not embedded; PDF/A requires all fonts to be embedded", name),
+            ConformanceError::Encryption => write!(f, "PDF/A does not allow encryption"),
+            ConformanceError::Transparency => write!(f, "PDF/A-1 does not allow transparency"),
+            ConformanceError::Artwork3D => write!(f, "PDF/A-1 does not allow 3D artwork"),
+            ConformanceError::EmbeddedFile => write!(f, "PDF/A-1 does not allow embedded files"),
+            ConformanceError::MissingPdfaId => write!(f, "XMP metadata of PDF/A must contain the pdfaid part and conformance"),
+        }
+    }
+}
+
+impl std::error::Error for ConformanceError {}
@@ -4,9 +4,9 @@ use std::ffi::CString;
 
 
 /// Page functions in Description mode or Text mode. 
-pub trait PageDescTeextCommonFunction<'doc> : Deref<Target=Page<'doc>> {
+pub trait PageDescTeextCommonFunction<'doc> {
     /// Get Page
-    fn handle(&self) -> &Page;
+    fn handle(&self) -> &Page<'_>;
     
     /// Set line width of page.
     fn set_line_width(&self, width: Real) -> anyhow::Result<()> {
@@ -163,7 +163,7 @@ pub trait PageDescTeextCommonFunction<'doc> : Deref<Target=Page<'doc>> {
 
     /// Print the text at the current position on the page.
     fn show_text(&self, text: &str) -> anyhow::Result<()> {
-        let text = CString::new(text)?;
+        let text = CString::new(self.handle().encode_text(text)?)?;
         let status = unsafe {
             libharu_sys::HPDF_Page_ShowText(self.handle().handle(), std::mem::transmute(text.as_ptr()))
         };
@@ -191,7 +191,7 @@ pub trait PageDescTeextCommonFunction<'doc> : Deref<Target=Page<'doc>> {
 
     /// Move the current text position to the start of the next line,
     fn show_text_next_line(&self, text: &str) -> anyhow::Result<()> {
-        let text = CString::new(text)?;
+        let text = CString::new(self.handle().encode_text(text)?)?;
         let status = unsafe {
             libharu_sys::HPDF_Page_ShowTextNextLine(self.handle().handle(), std::mem::transmute(text.as_ptr()))
         };
@@ -220,7 +220,7 @@ pub trait PageDescTeextCommonFunction<'doc> : Deref<Target=Page<'doc>> {
     /// Moves the current text position to the start of the next line, then sets the word spacing,
     /// character spacing and prints the text at the current position on the page.
     fn show_text_next_line_ex(&self, word_space: Real, char_space: Real, text: &str) -> anyhow::Result<()> {
-        let text = CString::new(text)?;
+        let text = CString::new(self.handle().encode_text(text)?)?;
         let status = unsafe {
             libharu_sys::HPDF_Page_ShowTextNextLineEx(self.handle().handle(), word_space, char_space, std::mem::transmute(text.as_ptr()))
         };
@@ -344,14 +344,80 @@ pub trait PageDescTeextCommonFunction<'doc> : Deref<Target=Page<'doc>> {
 
         Ok(())
     }
+
+    /// Set the filling color in an ICC based color space.
+    fn set_icc_fill(&self, profile: &IccProfile, components: &[Real]) -> anyhow::Result<()> {
+        self.handle().set_icc_color(profile, components, false)
+    }
+
+    /// Set the stroking color in an ICC based color space.
+    fn set_icc_stroke(&self, profile: &IccProfile, components: &[Real]) -> anyhow::Result<()> {
+        self.handle().set_icc_color(profile, components, true)
+    }
 }
 
 
 //------------------------------------------------------------------------------
 /// Page functions in Description mode or Path mode.
-pub trait PageDescPathCommonFunction<'doc> : Deref<Target=Page<'doc>> {
+pub trait PageDescPathCommonFunction<'doc> {
     /// Get Page
-    fn handle(&self) -> &Page;
+    fn handle(&self) -> &Page<'_>;
+}
+
+
+//--------------------------------------------------------------------------
+/// Origin of the coordinates passed to the page functions.
+///
+/// With `TopLeft`, y coordinates are measured downward from the top edge of the page, and
+/// the position of a rectangle, an image or a text rect is its top-left corner. Text is still
+/// written upright, `y` of `text_out` is the baseline, and the angles of `arc` keep their
+/// meaning. Transformation matrices (`concat`, `set_text_matrix`) are not converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Bottom-left corner of the page, y axis going up. (PDF default)
+    BottomLeft,
+
+    /// Top-left corner of the page, y axis going down.
+    TopLeft,
+}
+
+impl Copy for Origin {}
+
+impl Origin {
+    /// Convert a point into PDF coordinates. (and back, the conversion is its own inverse)
+    fn point(self, page: &Page, pos: Point) -> anyhow::Result<Point> {
+        match self {
+            Origin::BottomLeft => Ok(pos),
+            Origin::TopLeft => Ok(Point { x: pos.x, y: page.height()? - pos.y }),
+        }
+    }
+
+    /// Convert a relative offset into PDF coordinates.
+    fn offset(self, pos: Point) -> Point {
+        match self {
+            Origin::BottomLeft => pos,
+            Origin::TopLeft => Point { x: pos.x, y: -pos.y },
+        }
+    }
+
+    /// Convert the position of a box into its bottom-left corner in PDF coordinates.
+    fn corner(self, page: &Page, pos: Point, height: Real) -> anyhow::Result<Point> {
+        match self {
+            Origin::BottomLeft => Ok(pos),
+            Origin::TopLeft => Ok(Point { x: pos.x, y: page.height()? - pos.y - height }),
+        }
+    }
+
+    /// Convert a rect into PDF coordinates.
+    fn rect(self, page: &Page, rect: Rect) -> anyhow::Result<Rect> {
+        match self {
+            Origin::BottomLeft => Ok(rect),
+            Origin::TopLeft => {
+                let height = page.height()?;
+                Ok(Rect { left: rect.left, top: height - rect.top, right: rect.right, bottom: height - rect.bottom })
+            },
+        }
+    }
 }
 
 
@@ -359,12 +425,287 @@ pub trait PageDescPathCommonFunction<'doc> : Deref<Target=Page<'doc>> {
 /// Page object in Description mode.
 pub struct PageDescriptionMode<'doc, 'page> {
     page: &'page Page<'doc>,
+    origin: Origin,
 }
 
 impl<'doc, 'page> PageDescriptionMode<'doc, 'page> {
     /// Create new PageDescriptionMode instance.
     pub fn new(page: &'page Page<'doc>) -> Self {
-        Self { page }
+        Self { page, origin: Origin::BottomLeft }
+    }
+
+    /// Set the origin of the coordinates, used by this object and its text and path modes.
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Get the origin of the coordinates.
+    pub fn origin(&self) -> Origin {
+        self.origin
+    }
+
+    /// Get the page, whose functions take PDF coordinates regardless of the origin.
+    pub fn as_page(&self) -> &'page Page<'doc> {
+        self.page
+    }
+
+    /// Get height of page.
+    pub fn height(&self) -> anyhow::Result<Real> {
+        self.page.height()
+    }
+
+    /// Set height of page.
+    pub fn set_height(&self, val: Real) -> anyhow::Result<()> {
+        self.page.set_height(val)
+    }
+
+    /// Get width of page.
+    pub fn width(&self) -> anyhow::Result<Real> {
+        self.page.width()
+    }
+
+    /// Set width of page.
+    pub fn set_width(&self, val: Real) -> anyhow::Result<()> {
+        self.page.set_width(val)
+    }
+
+    /// Push the page's current graphics state to the stack.
+    pub fn gsave(&self) -> anyhow::Result<()> {
+        self.page.gsave()
+    }
+
+    /// Pop the graphics state from the stack.
+    pub fn grestore(&self) -> anyhow::Result<()> {
+        self.page.grestore()
+    }
+
+    /// Get line width of page.
+    pub fn line_width(&self) -> Real {
+        self.page.line_width()
+    }
+
+    /// Clear the line dash pattern in the page.
+    pub fn clear_dash(&self) -> anyhow::Result<()> {
+        self.page.clear_dash()
+    }
+
+    /// Get current value of the page's filling color
+    pub fn rgb_fill(&self) -> anyhow::Result<Color> {
+        self.page.rgb_fill()
+    }
+
+    /// Gets the handle of the page's current font.
+    pub fn current_font(&self) -> anyhow::Result<Font<'_>> {
+        self.page.current_font()
+    }
+
+    /// Gets the size of the page's current font.
+    pub fn current_font_size(&self) -> anyhow::Result<Real> {
+        self.page.current_font_size()
+    }
+
+    /// Get the current value of the page's line spacing.
+    pub fn text_leading(&self) -> anyhow::Result<Real> {
+        self.page.text_leading()
+    }
+
+    /// Get the width of the text in current fontsize, character spacing and word spacing.
+    pub fn text_width(&self, txt: &str) -> anyhow::Result<Real> {
+        self.page.text_width(txt)
+    }
+
+    /// Calculate the byte length which can be included within the specified width.
+    pub fn measure_text(&self, text: &str, width: Real, wordwrap: bool) -> anyhow::Result<(usize, Real)> {
+        self.page.measure_text(text, width, wordwrap)
+    }
+
+    /// Create a new destination object for the page.
+    pub fn create_destination(&self) -> anyhow::Result<Destination<'page, 'doc>> {
+        self.page.create_destination()
+    }
+
+    /// Draw the XObject using the current transformation matrix.
+    pub fn execute_xobject(&self, xobj: &XObject) -> anyhow::Result<()> {
+        self.page.execute_xobject(xobj)
+    }
+
+    /// Convert a point in the coordinates of this object into PDF coordinates.
+    pub fn map_point<T>(&self, pos: T) -> anyhow::Result<Point>
+    where
+        T: Into<Point>
+    {
+        self.origin.point(self.page, pos.into())
+    }
+
+    /// Convert a rect in the coordinates of this object into PDF coordinates.
+    pub fn map_rect<T>(&self, rect: T) -> anyhow::Result<Rect>
+    where
+        T: Into<Rect>
+    {
+        self.origin.rect(self.page, rect.into())
+    }
+
+    /// Get the current position for path painting.
+    pub fn current_pos(&self) -> anyhow::Result<Point> {
+        self.origin.point(self.page, self.page.current_pos()?)
+    }
+
+    /// Show an image in one operation.
+    pub fn draw_image<T>(&self, img: &Image, pos: T, width: Real, height: Real) -> anyhow::Result<()>
+    where
+        T: Into<Point>
+    {
+        let pos = self.origin.corner(self.page, pos.into(), height)?;
+
+        self.page.draw_image(img, pos, width, height)
+    }
+
+    /// Create a new link annotation object for the page.
+    pub fn create_link_annot<T>(&self, rect: T, dst: &Destination<'_, '_>) -> anyhow::Result<LinkAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_link_annot(rect, dst)
+    }
+
+    /// Create a new link annotation which jumps to the named destination `name` of another PDF file.
+    pub fn create_remote_link_annot<T>(&self, rect: T, file: &str, name: &str) -> anyhow::Result<LinkAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_remote_link_annot(rect, file, name)
+    }
+
+    /// Create a new text markup annotation which marks the areas of `quads`.
+    pub fn create_text_markup_annot(&self, kind: TextMarkupKind, quads: &[Quad], contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>> {
+        let quads = quads.iter().map(|quad| {
+            Ok(Quad {
+                top_left: self.map_point(quad.top_left)?,
+                top_right: self.map_point(quad.top_right)?,
+                bottom_left: self.map_point(quad.bottom_left)?,
+                bottom_right: self.map_point(quad.bottom_right)?,
+            })
+        }).collect::<anyhow::Result<Vec<Quad>>>()?;
+
+        self.page.create_text_markup_annot(kind, &quads, contents)
+    }
+
+    /// Create a new highlight annotation for the page.
+    pub fn create_highlight_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_highlight_annot(rect, contents)
+    }
+
+    /// Create a new underline annotation for the page.
+    pub fn create_underline_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_underline_annot(rect, contents)
+    }
+
+    /// Create a new squiggly underline annotation for the page.
+    pub fn create_squiggly_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_squiggly_annot(rect, contents)
+    }
+
+    /// Create a new strike-out annotation for the page.
+    pub fn create_strike_out_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_strike_out_annot(rect, contents)
+    }
+
+    /// Create a new free text annotation, which displays the text directly on the page.
+    pub fn create_free_text_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<FreeTextAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_free_text_annot(rect, contents)
+    }
+
+    /// Create a new line annotation from `start` to `end`.
+    pub fn create_line_annot<T1, T2>(&self, start: T1, end: T2, contents: &str) -> anyhow::Result<LineAnnotation<'_, '_>>
+    where
+        T1: Into<Point>,
+        T2: Into<Point>,
+    {
+        let start = self.map_point(start)?;
+        let end = self.map_point(end)?;
+
+        self.page.create_line_annot(start, end, contents)
+    }
+
+    /// Create a new square annotation, which displays a rectangle on the page.
+    pub fn create_square_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<ShapeAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_square_annot(rect, contents)
+    }
+
+    /// Create a new circle annotation, which displays an ellipse inscribed in the rect.
+    pub fn create_circle_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<ShapeAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_circle_annot(rect, contents)
+    }
+
+    /// Create a new stamp annotation with a predefined icon.
+    pub fn create_stamp_annot<T>(&self, rect: T, name: StampName, contents: &str) -> anyhow::Result<StampAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_stamp_annot(rect, name, contents)
+    }
+
+    /// Create a new popup annotation, which displays the comment of `parent` in the rect.
+    pub fn create_popup_annot<T, A>(&self, rect: T, parent: &A) -> anyhow::Result<PopupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>,
+        A: MarkupAnnotation,
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_popup_annot(rect, parent)
+    }
+
+    /// Create a new 3D annotation which shows the 3D artwork in `rect`.
+    pub fn create_3d_annot<T>(&self, rect: T, u3d: &U3D<'_>) -> anyhow::Result<Annotation3D<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let rect = self.map_rect(rect)?;
+
+        self.page.create_3d_annot(rect, u3d)
     }
 
     fn begin_text(&self) -> anyhow::Result<()> {
@@ -397,7 +738,7 @@ impl<'doc, 'page> PageDescriptionMode<'doc, 'page> {
         F: FnOnce(&PageTextMode) -> anyhow::Result<()>
     {
         self.begin_text()?;
-        let page = PageTextMode::new(&self.page);
+        let page = PageTextMode::new(&self.page, self.origin);
         let ret = f(&page);
         self.end_text()?;
 
@@ -420,7 +761,7 @@ impl<'doc, 'page> PageDescriptionMode<'doc, 'page> {
     where
         F: FnOnce(&PagePathMode) -> anyhow::Result<()>
     {
-        let page = PagePathMode::new(&self.page);
+        let page = PagePathMode::new(&self.page, self.origin);
         let ret = f(&page);
 
         // f()内のstroke(), fill()などの呼び出しでDESCRIPTIONモードに戻る。
@@ -432,21 +773,14 @@ impl<'doc, 'page> PageDescriptionMode<'doc, 'page> {
     }
 }
 
-impl<'doc, 'page> Deref for PageDescriptionMode<'doc, 'page> {
-    type Target = Page<'doc>;
-    fn deref(&self) -> &Self::Target {
-        self.page
-    }
-}
-
 impl<'doc, 'page> PageDescTeextCommonFunction<'doc> for PageDescriptionMode<'doc, 'page> {
-    fn handle(&self) -> &Page {
+    fn handle(&self) -> &Page<'_> {
         &self.page
     }
 }
 
 impl<'doc, 'page> PageDescPathCommonFunction<'doc> for PageDescriptionMode<'doc, 'page> {
-    fn handle(&self) -> &Page {
+    fn handle(&self) -> &Page<'_> {
         &self.page
     }
 }
@@ -456,11 +790,17 @@ impl<'doc, 'page> PageDescPathCommonFunction<'doc> for PageDescriptionMode<'doc,
 /// Page object in text mode.
 pub struct PageTextMode<'doc, 'page> {
     page: &'page Page<'doc>,
+    origin: Origin,
 }
 
 impl<'doc, 'page> PageTextMode<'doc, 'page> {
-    pub(crate) fn new(page: &'page Page<'doc>) -> Self {
-        Self { page }
+    pub(crate) fn new(page: &'page Page<'doc>, origin: Origin) -> Self {
+        Self { page, origin }
+    }
+
+    /// Get the current position for text showing.
+    pub fn current_text_pos(&self) -> anyhow::Result<Point> {
+        self.origin.point(self.page, self.page.current_text_pos()?)
     }
     
     /// Move the current text position to the start of the next line with using specified offset values.
@@ -468,7 +808,7 @@ impl<'doc, 'page> PageTextMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.offset(pos.into());
 
         let status = unsafe {
             libharu_sys::HPDF_Page_MoveTextPos(self.page.handle(), pos.x, pos.y)
@@ -486,7 +826,7 @@ impl<'doc, 'page> PageTextMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.offset(pos.into());
 
         let status = unsafe {
             libharu_sys::HPDF_Page_MoveTextPos2(self.page.handle(), pos.x, pos.y)
@@ -530,8 +870,8 @@ impl<'doc, 'page> PageTextMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
-        let text = CString::new(text)?;
+        let pos = self.origin.point(self.page, pos.into())?;
+        let text = CString::new(self.page.encode_text(text)?)?;
         let status = unsafe {
             libharu_sys::HPDF_Page_TextOut(self.page.handle(), pos.x, pos.y, std::mem::transmute(text.as_ptr()))
         };
@@ -548,7 +888,7 @@ impl<'doc, 'page> PageTextMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.point(self.page, pos.into())?;
         let text = CString::new(text)?;
         let status = unsafe {
             libharu_sys::HPDF_Page_TextOut(self.page.handle(), pos.x, pos.y, std::mem::transmute(text.as_ptr()))
@@ -566,8 +906,8 @@ impl<'doc, 'page> PageTextMode<'doc, 'page> {
     where
         T: Into<Rect>
     {
-        let rect = rect.into();
-        let text = CString::new(text)?;
+        let rect = self.origin.rect(self.page, rect.into())?;
+        let text = CString::new(self.page.encode_text(text)?)?;
         let align = match align {
             TextAlignment::Left => libharu_sys::HPDF_TextAlignment::HPDF_TALIGN_LEFT,
             TextAlignment::Right => libharu_sys::HPDF_TextAlignment::HPDF_TALIGN_RIGHT,
@@ -591,7 +931,7 @@ impl<'doc, 'page> PageTextMode<'doc, 'page> {
     where
         T: Into<Rect>
     {
-        let rect = rect.into();
+        let rect = self.origin.rect(self.page, rect.into())?;
         let text = CString::new(text)?;
         let align = match align {
             TextAlignment::Left => libharu_sys::HPDF_TextAlignment::HPDF_TALIGN_LEFT,
@@ -621,7 +961,7 @@ impl<'doc, 'page> Deref for PageTextMode<'doc, 'page> {
 
 
 impl<'doc, 'page> PageDescTeextCommonFunction<'doc> for PageTextMode<'doc, 'page> {
-    fn handle(&self) -> &Page {
+    fn handle(&self) -> &Page<'_> {
         &self.page
     }
 }
@@ -632,11 +972,17 @@ impl<'doc, 'page> PageDescTeextCommonFunction<'doc> for PageTextMode<'doc, 'page
 /// Page object in Path mode.
 pub struct PagePathMode<'doc, 'page> {
     page: &'page Page<'doc>,
+    origin: Origin,
 }
 
 impl<'doc, 'page> PagePathMode<'doc, 'page> {
-    pub(crate) fn new(page: &'page Page<'doc>) -> Self {
-        Self { page }
+    pub(crate) fn new(page: &'page Page<'doc>, origin: Origin) -> Self {
+        Self { page, origin }
+    }
+
+    /// Get the current position for path painting.
+    pub fn current_pos(&self) -> anyhow::Result<Point> {
+        self.origin.point(self.page, self.page.current_pos()?)
     }
     
     /// Start a new subpath and move the current point for drawing path,
@@ -644,7 +990,7 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.point(self.page, pos.into())?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_MoveTo(self.page.handle(), pos.x, pos.y)
@@ -664,9 +1010,9 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
         T2: Into<Point>,
         T3: Into<Point>,
     {
-        let point1 = point1.into();
-        let point2 = point2.into();
-        let point3 = point3.into();
+        let point1 = self.origin.point(self.page, point1.into())?;
+        let point2 = self.origin.point(self.page, point2.into())?;
+        let point3 = self.origin.point(self.page, point3.into())?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_CurveTo(self.page.handle(), point1.x, point1.y, point2.x, point2.y, point3.x, point3.y)
@@ -685,8 +1031,8 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
         T1: Into<Point>,
         T2: Into<Point>,
     {
-        let point2 = point2.into();
-        let point3 = point3.into();
+        let point2 = self.origin.point(self.page, point2.into())?;
+        let point3 = self.origin.point(self.page, point3.into())?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_CurveTo2(self.page.handle(), point2.x, point2.y, point3.x, point3.y)
@@ -705,8 +1051,8 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
         T1: Into<Point>,
         T2: Into<Point>,
     {
-        let point1 = point1.into();
-        let point3 = point3.into();
+        let point1 = self.origin.point(self.page, point1.into())?;
+        let point3 = self.origin.point(self.page, point3.into())?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_CurveTo3(self.page.handle(), point1.x, point1.y, point3.x, point3.y)
@@ -724,7 +1070,7 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
     where
         T: Into<Point>,
     {
-        let pos = pos.into();
+        let pos = self.origin.point(self.page, pos.into())?;
         let status = unsafe {
             libharu_sys::HPDF_Page_LineTo(self.page.handle(), pos.x, pos.y)
         };
@@ -737,11 +1083,13 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
     }
 
     /// Append a rectangle to the current path.
+    ///
+    /// `pos` is the corner nearest to the origin. (the top-left corner with `Origin::TopLeft`)
     pub fn rectangle<T>(&self, pos: T, width: Real, height: Real) -> anyhow::Result<()>
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.corner(self.page, pos.into(), height)?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_Rectangle(self.page.handle(), pos.x, pos.y, width, height)
@@ -759,7 +1107,7 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.point(self.page, pos.into())?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_Circle(self.page.handle(), pos.x, pos.y, ray)
@@ -777,7 +1125,7 @@ impl<'doc, 'page> PagePathMode<'doc, 'page> {
     where
         T: Into<Point>
     {
-        let pos = pos.into();
+        let pos = self.origin.point(self.page, pos.into())?;
 
         let status = unsafe {
             libharu_sys::HPDF_Page_Arc(self.page.handle(), pos.x, pos.y, ray, ang1, ang2)
@@ -853,7 +1201,52 @@ impl<'doc, 'page> Deref for PagePathMode<'doc, 'page> {
 }
 
 impl<'doc, 'page> PageDescPathCommonFunction<'doc> for PagePathMode<'doc, 'page> {
-    fn handle(&self) -> &Page {
+    fn handle(&self) -> &Page<'_> {
         self.page
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_maps_rect_and_corner() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let page = doc.add_page().unwrap();
+        page.set_height(800.0).unwrap();
+
+        let rect = Rect { left: 10.0, top: 20.0, right: 110.0, bottom: 70.0 };
+        assert_eq!(Origin::BottomLeft.rect(&page, rect).unwrap(), rect);
+        assert_eq!(Origin::TopLeft.rect(&page, rect).unwrap(), Rect { left: 10.0, top: 780.0, right: 110.0, bottom: 730.0 });
+
+        /* the top-left corner of a box of 50pt is moved to its bottom-left corner */
+        let pos = Point { x: 10.0, y: 20.0 };
+        assert_eq!(Origin::BottomLeft.corner(&page, pos, 50.0).unwrap(), pos);
+        assert_eq!(Origin::TopLeft.corner(&page, pos, 50.0).unwrap(), Point { x: 10.0, y: 730.0 });
+
+        /* points and offsets convert back into the same values */
+        let back = Origin::TopLeft.point(&page, Origin::TopLeft.point(&page, pos).unwrap()).unwrap();
+        assert_eq!(back, pos);
+        assert_eq!(Origin::TopLeft.offset(Point { x: 5.0, y: 5.0 }), Point { x: 5.0, y: -5.0 });
+    }
+
+    #[test]
+    fn description_mode_forwards_to_its_page() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let page = doc.add_page().unwrap();
+
+        let desc = PageDescriptionMode::new(&page).with_origin(Origin::TopLeft);
+        assert_eq!(desc.as_page().handle(), page.handle());
+
+        desc.set_height(800.0).unwrap();
+        assert_eq!(page.height().unwrap(), 800.0);
+        assert_eq!(desc.map_point((10.0, 20.0)).unwrap(), Point { x: 10.0, y: 780.0 });
+
+        desc.gsave().unwrap();
+        desc.set_line_width(3.0).unwrap();
+        assert_eq!(desc.line_width(), 3.0);
+        desc.grestore().unwrap();
+        assert_eq!(page.line_width(), 1.0);
+    }
+}
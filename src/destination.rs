@@ -1,22 +1,43 @@
+use crate::document::Document;
 use crate::page::Page;
-use crate::Real;
+use crate::error::Error;
+use crate::{ffi, Real};
+
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+/// Maximum length of the name of a named destination.
+const MAX_NAME_LEN: usize = 127;
 
 /// Destination handle type.
 pub struct Destination<'a, 'b> {
     dst: libharu_sys::HPDF_Destination,
-    _page: &'a Page<'b>,
+    doc: &'b Document,
+    _page: PhantomData<&'a Page<'b>>,
 }
 
 impl<'a, 'b> Destination<'a, 'b> {
-    pub(crate) fn new(_page: &'a Page<'b>, dst: libharu_sys::HPDF_Destination) -> Self {
-        Self { dst, _page }
+    pub(crate) fn new(page: &'a Page<'b>, dst: libharu_sys::HPDF_Destination) -> Self {
+        Self { dst, doc: page.doc(), _page: PhantomData }
     }
     
     #[inline]
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Destination {
+        self.doc.enter_memory();
         self.dst
     }
 
+    /// Get the document which the destination belongs to.
+    #[inline]
+    pub(crate) fn doc(&self) -> &'b Document {
+        self.doc
+    }
+
+    /// Get the destination without the borrow of its page; the page lives as long as the document.
+    pub(crate) fn detached(&self) -> Destination<'b, 'b> {
+        Destination { dst: self.dst, doc: self.doc, _page: PhantomData }
+    }
+
     /// Define the appearance of a page with three parameters which are left, top and zoom.
     pub fn set_xyz(&self, left: Real, top: Real, zoom: Real) -> anyhow::Result<()> {
         let status = unsafe {
@@ -28,4 +49,28 @@ impl<'a, 'b> Destination<'a, 'b> {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Check the name of a named destination.
+///
+/// Named destinations were name objects before PDF 1.2, so the names are limited to the length of name objects.
+pub(crate) fn validate_name(name: &str) -> anyhow::Result<()> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(Error::NameOutOfRange.into());
+    }
+
+    Ok(())
+}
+
+/// Create a string object for the name of a named destination.
+pub(crate) unsafe fn name_string_new(mmgr: libharu_sys::HPDF_MMgr, name: &str) -> anyhow::Result<libharu_sys::HPDF_HANDLE> {
+    validate_name(name)?;
+
+    let name = CString::new(name)?;
+    let obj = ffi::HPDF_String_New(mmgr, name.as_ptr(), std::ptr::null_mut());
+    if obj.is_null() {
+        anyhow::bail!("HPDF_String_New failed");
+    }
+
+    Ok(obj)
+}
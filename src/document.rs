@@ -1,17 +1,29 @@
 #![warn(missing_docs)]
 
-use crate::error::Error;
-use crate::page::Page;
-use crate::outline::Outline;
+use crate::error::{Error, ErrorDetail};
+use crate::page::{Page, PageSize, PageDirection};
+use crate::outline::{Outline, OutlineNode, OutlineTitle, OutlineTree};
 use crate::Font;
 use crate::encoder::Encoder;
 use crate::destination::Destination;
 use crate::image::Image;
+use crate::xobject::XObject;
+use crate::embedded_file::EmbeddedFile;
+use crate::context::PageDescriptionMode;
+use crate::conformance::{Conformance, ConformanceError};
+use crate::info::{InfoType, InfoDate, InfoValues};
+use crate::xmp::XmpMetadata;
+use crate::icc::{IccProfile, OutputIntent};
+use crate::memory::{Accounting, MemoryStats};
+use crate::u3d::U3D;
+use crate::{ffi, Real};
 
 use bitflags::bitflags;
 
 use std::ffi::CString;
+use std::io::Read;
 use std::convert::TryInto;
+use std::cell::{Cell, RefCell};
 
 /// Page label style.
 #[derive(Debug)]
@@ -52,6 +64,33 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// The flags specifying how the viewer application should present the document.
+    pub struct ViewerPreferences: u32 {
+        /// Hide the toolbar of the viewer.
+        const HIDE_TOOLBAR       = 0x01;
+
+        /// Hide the menu bar of the viewer.
+        const HIDE_MENUBAR       = 0x02;
+
+        /// Hide the user interface elements such as scroll bars, and display only the contents.
+        const HIDE_WINDOW_UI     = 0x04;
+
+        /// Resize the window of the viewer to fit the first page.
+        const FIT_WINDOW         = 0x08;
+
+        /// Position the window of the viewer in the center of the screen.
+        const CENTER_WINDOW      = 0x10;
+
+        /// Print the pages in their actual size instead of scaling them to the paper. (PDF 1.6,
+        /// requires the `bundled-internals` feature)
+        const PRINT_SCALING_NONE = 0x20;
+    }
+}
+
+/// Size of the chunks copied from the internal memory stream when saving.
+const SAVE_CHUNK_SIZE: usize = 8192;
+
 /// Page display style.
 #[derive(Debug)]
 pub enum PageMode {
@@ -66,6 +105,9 @@ pub enum PageMode {
     
     /// Display the document with full screen mode.
     FullScreen,
+
+    /// Display the document with attachments pane. (PDF 1.6, requires the `bundled-internals` feature)
+    Attachments,
 }
 
 
@@ -83,53 +125,364 @@ pub enum PageLayout {
     
     /// Display the pages in two column. The page of the odd number is displayed right.
     TwoColumnRight,
+
+    /// Display the pages two at a time. The page of the odd number is displayed left. (PDF 1.5)
+    TwoPageLeft,
+
+    /// Display the pages two at a time. The page of the odd number is displayed right. (PDF 1.5)
+    TwoPageRight,
 }
 // onerrorのクロージャをBoxで持ちたいためInnerを別にしている。
-// TODO: onerrorは必要か？
 struct DocumentInner {
-    onerror: Box<dyn Fn(Error)>,
-    last_errno: libharu_sys::HPDF_STATUS,
-    last_detailno: libharu_sys::HPDF_STATUS,
+    onerror: Box<dyn Fn(ErrorDetail) + Send>,
+    fatal_error: Cell<Option<(libharu_sys::HPDF_STATUS, libharu_sys::HPDF_STATUS)>>,
+    conformance: Cell<Conformance>,
+    info: RefCell<InfoValues>,
+    embedded_fonts: RefCell<Vec<String>>,
+    dest_names: RefCell<Vec<String>>,
+    xmp: RefCell<Option<XmpMetadata>>,
+    memory: Option<Accounting>,
+    pages_per_pages: Option<u32>,
+    has_doc: Cell<bool>,
+    page_count: Cell<usize>,
+    current_encoder: RefCell<Option<String>>,
+    print_scaling_none: Cell<bool>,
 }
 
-/// PDF Document handle type.
-/// 
-/// The document handle is a handle to operate a document object. 
-pub struct Document {
-    doc: libharu_sys::HPDF_Doc,
+/// Iterator over the pages of [`Document`].
+pub struct PageIter<'a> {
+    doc: &'a Document,
+    index: usize,
+    count: usize,
+}
 
-    #[allow(dead_code)]
-    inner: Box<DocumentInner>,
+impl<'a> Iterator for PageIter<'a> {
+    type Item = anyhow::Result<Page<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        /* a failure is yielded instead of ending the iteration, which would break the exact size */
+        let page = self.doc.page(self.index);
+        self.index += 1;
+
+        Some(page)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.count - self.index;
+        (len, Some(len))
+    }
 }
 
-impl Document {
-    /// Create a new instance of document.
-    pub fn new(onerror: impl Fn(Error) + 'static) -> anyhow::Result<Self>
-    {
-        let onerror = Box::new(onerror);
-        let mut inner = Box::new(DocumentInner{onerror, last_errno: 0, last_detailno: 0});
+impl<'a> ExactSizeIterator for PageIter<'a> {}
+
+/// Builder of document with custom memory settings.
+pub struct DocumentBuilder {
+    onerror: Box<dyn Fn(ErrorDetail) + Send>,
+    accounting: bool,
+    memory_limit: Option<usize>,
+    mem_pool_buf_size: u32,
+    pages_per_pages: Option<u32>,
+}
+
+impl DocumentBuilder {
+    fn new() -> Self {
+        Self {
+            onerror: Box::new(|_| {}),
+            accounting: false,
+            memory_limit: None,
+            mem_pool_buf_size: 0,
+            pages_per_pages: None,
+        }
+    }
+
+    /// Set the error handler, which is called when libharu reports an error.
+    ///
+    /// Without an error handler, errors are reported only through the returned `Result`s
+    /// and `Document::last_error`.
+    pub fn onerror(mut self, onerror: impl Fn(Error) + Send + 'static) -> Self {
+        self.onerror = Box::new(move |detail| onerror(detail.error));
+        self
+    }
+
+    /// Set the error handler, which receives the detail number of the error as well.
+    pub fn onerror_detail(mut self, onerror: impl Fn(ErrorDetail) + Send + 'static) -> Self {
+        self.onerror = Box::new(onerror);
+        self
+    }
+
+    /// Limit the memory allocated by libharu for the document. Allocations beyond the budget
+    /// fail with `Error::FailedToAllocMem`.
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.accounting = true;
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Track the memory allocated by libharu, see `Document::memory_stats`.
+    pub fn track_memory(mut self) -> Self {
+        self.accounting = true;
+        self
+    }
+
+    /// Enable the memory pool of libharu, which allocates memory in blocks of the given size.
+    pub fn mem_pool_buf_size(mut self, size: u32) -> Self {
+        self.mem_pool_buf_size = size;
+        self
+    }
+
+    /// Set the maximum number of pages per intermediate pages object, building a page tree
+    /// instead of a flat list of pages. (HPDF_SetPagesConfiguration)
+    pub fn pages_per_pages(mut self, count: u32) -> Self {
+        self.pages_per_pages = Some(count);
+        self
+    }
+
+    /// Create the document.
+    pub fn build(self) -> anyhow::Result<Document> {
+        let memory = match self.accounting {
+            true => Some(Accounting::new(self.memory_limit)),
+            false => None,
+        };
+        let (alloc_fn, free_fn) = match &memory {
+            Some(memory) => {
+                let (alloc_fn, free_fn) = memory.functions();
+                (Some(alloc_fn), Some(free_fn))
+            },
+            None => (None, None),
+        };
+
+        let mut inner = Box::new(DocumentInner{
+            onerror: self.onerror,
+            fatal_error: Cell::new(None),
+            conformance: Cell::new(Conformance::None),
+            info: RefCell::new(InfoValues::default()),
+            embedded_fonts: RefCell::new(Vec::new()),
+            dest_names: RefCell::new(Vec::new()),
+            xmp: RefCell::new(None),
+            memory,
+            pages_per_pages: self.pages_per_pages,
+            has_doc: Cell::new(true),
+            page_count: Cell::new(0),
+            current_encoder: RefCell::new(None),
+            print_scaling_none: Cell::new(false),
+        });
+
+        if let Some(memory) = &inner.memory {
+            memory.enter();
+        }
 
         let doc = unsafe {
-            libharu_sys::HPDF_New(
+            ffi::HPDF_NewEx(
                 onerror_callback,
+                alloc_fn,
+                free_fn,
+                self.mem_pool_buf_size,
                 std::mem::transmute(inner.as_mut()),
             )
         };
         
+        /* the document setup only fails when memory can not be allocated */
         if doc == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_New() failed");
+            return Err(Error::FailedToAllocMem.into());
         }
 
-        Ok(Self { doc, inner })
+        let doc = Document { doc, inner };
+        doc.init_doc()?;
+
+        Ok(doc)
+    }
+}
+
+/// PDF Document handle type.
+/// 
+/// The document handle is a handle to operate a document object. 
+///
+/// # Thread safety
+///
+/// `Document` is `Send`: a document can be moved to another thread, and separate documents
+/// can be built on different threads at the same time. It is not `Sync`, so one document
+/// is never operated from two threads at once.
+///
+/// # Error recovery
+///
+/// After libharu reports an error, the document stays in error state and later calls fail.
+/// The loading functions (`font`, `find_encoder`, `set_current_encoder`, `load_ttf_font`,
+/// `load_ttf_font_from_ttc` and `load_png_image`) leave the document unchanged when they fail,
+/// and clear a recoverable error before returning it. After other failures, such as a drawing
+/// function called in a wrong graphics mode, call `Document::recover`. A document with an error
+/// which is not recoverable (see `Error::is_recoverable`) can only be discarded with `Document::reset`.
+pub struct Document {
+    doc: libharu_sys::HPDF_Doc,
+
+    inner: Box<DocumentInner>,
+}
+
+/* Each HPDF_Doc owns all of its objects, and libharu has no global mutable state. The error
+ * handler is required to be Send, and the allocator of memory accounting is thread safe. */
+unsafe impl Send for Document {}
+
+impl Document {
+    /// Create a new instance of document.
+    pub fn new(onerror: impl Fn(Error) + Send + 'static) -> anyhow::Result<Self>
+    {
+        Self::builder().onerror(onerror).build()
+    }
+
+    /// Create a builder of document with custom memory settings.
+    pub fn builder() -> DocumentBuilder {
+        DocumentBuilder::new()
     }
 
     #[inline]
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Doc {
+        self.enter_memory();
         self.doc
     }
 
+    /// Charge the memory allocated by libharu on this thread to the document, if it was built
+    /// with memory accounting. Called by the handle getters of all objects of the document.
+    #[inline]
+    pub(crate) fn enter_memory(&self) {
+        if let Some(memory) = &self.inner.memory {
+            memory.enter();
+        }
+    }
+
+    /// Discard the current document and start a new one on the same handle.
+    ///
+    /// Loaded fonts and encodings are kept, so they need not be loaded again.
+    pub fn reset(&mut self) -> anyhow::Result<()> {
+        let status = unsafe {
+            ffi::HPDF_NewDoc(self.handle())
+        };
+
+        self.inner.fatal_error.set(None);
+        if let Some(memory) = &self.inner.memory {
+            memory.clear_exhausted();
+        }
+        self.inner.conformance.set(Conformance::None);
+        *self.inner.info.borrow_mut() = InfoValues::default();
+        *self.inner.xmp.borrow_mut() = None;
+        self.inner.dest_names.borrow_mut().clear();
+        self.inner.has_doc.set(status == 0);
+        self.inner.page_count.set(0);
+        *self.inner.current_encoder.borrow_mut() = None;
+        self.inner.print_scaling_none.set(false);
+
+        if status != 0 {
+            anyhow::bail!("HPDF_NewDoc failed (status={})", status);
+        }
+
+        self.init_doc()
+    }
+
+    /// Discard the current document along with loaded fonts and encodings, and start a new one.
+    pub fn reset_all(&mut self) -> anyhow::Result<()> {
+        unsafe {
+            ffi::HPDF_FreeDocAll(self.handle());
+        }
+
+        self.inner.embedded_fonts.borrow_mut().clear();
+
+        self.reset()
+    }
+
+    /// Get the error reported by libharu, if the document is in error state.
+    pub fn last_error(&self) -> Option<ErrorDetail> {
+        let (error_no, detail_no) = unsafe {
+            (ffi::HPDF_GetError(self.handle()), ffi::HPDF_GetErrorDetail(self.handle()))
+        };
+
+        if error_no == 0 {
+            return None;
+        }
+
+        Some(ErrorDetail { error: Error::from_num(error_no), detail_no })
+    }
+
+    /// Clear the error state of the document.
+    pub fn reset_error(&self) {
+        unsafe {
+            ffi::HPDF_ResetError(self.handle());
+        }
+    }
+
+    /// Clear a recoverable error, so that the document can be used again.
+    ///
+    /// Returns the error if it is not recoverable; such a document can only be discarded
+    /// with `Document::reset`.
+    pub fn recover(&self) -> anyhow::Result<()> {
+        if let Some((error_no, detail_no)) = self.inner.fatal_error.get() {
+            return Err(ErrorDetail { error: Error::from_num(error_no), detail_no }.into());
+        }
+
+        /* an allocation beyond the memory budget may be reported as another error */
+        if self.inner.memory.as_ref().is_some_and(|memory| memory.exhausted()) {
+            return Err(ErrorDetail { error: Error::FailedToAllocMem, detail_no: 0 }.into());
+        }
+
+        if !self.inner.has_doc.get() {
+            return Err(Error::InvalidDocument.into());
+        }
+
+        self.reset_error();
+
+        Ok(())
+    }
+
+    /// Make the error of a failed libharu function, and clear the error state if it is recoverable.
+    fn failure(&self, func: &str) -> anyhow::Error {
+        let error = match self.last_error() {
+            Some(detail) => anyhow::Error::new(detail).context(format!("{} failed", func)),
+            None => anyhow::anyhow!("{} failed", func),
+        };
+
+        let _ = self.recover();
+
+        error
+    }
+
+    /// Check whether the handle has a valid document.
+    pub fn has_doc(&self) -> bool {
+        /* HPDF_HasDoc raises an error when there is no document, so keep track of it here */
+        self.inner.has_doc.get() && self.last_error().is_none()
+    }
+
+    /// Set up the Rust side state of a newly created document.
+    fn init_doc(&self) -> anyhow::Result<()> {
+        /* keep the producer set by libharu, so that it can be read back */
+        let producer = unsafe {
+            ffi::HPDF_GetInfoAttr(self.handle(), InfoType::Producer.to_sys())
+        };
+        if !producer.is_null() {
+            let producer = unsafe { std::ffi::CStr::from_ptr(producer) };
+            self.inner.info.borrow_mut().text.insert(InfoType::Producer, producer.to_string_lossy().into_owned());
+        }
+
+        if let Some(count) = self.inner.pages_per_pages {
+            let status = unsafe {
+                ffi::HPDF_SetPagesConfiguration(self.handle(), count)
+            };
+
+            if status != 0 {
+                anyhow::bail!("HPDF_SetPagesConfiguration failed (status={})", status);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the memory usage of the document, if it was built with memory accounting.
+    pub fn memory_stats(&self) -> Option<MemoryStats> {
+        self.inner.memory.as_ref().map(|memory| memory.stats())
+    }
+
     /// Create a new page and adds it after the last page of a document.
-    pub fn add_page(&self) -> anyhow::Result<Page> {
+    pub fn add_page(&self) -> anyhow::Result<Page<'_>> {
         let page = unsafe {
             libharu_sys::HPDF_AddPage(self.handle())
         };
@@ -138,11 +491,59 @@ impl Document {
             anyhow::bail!("HPDF_AddPage failed");
         }
 
+        self.inner.page_count.set(self.inner.page_count.get() + 1);
+
+        Ok(Page::new(self, page))
+    }
+
+    /// Create a new page with the size and direction, and adds it after the last page of a document.
+    pub fn add_page_with(&self, size: PageSize, direction: PageDirection) -> anyhow::Result<Page<'_>> {
+        let page = self.add_page()?;
+        page.set_size(size, direction)?;
+
+        Ok(page)
+    }
+
+    /// Get the number of pages in the document.
+    pub fn page_count(&self) -> usize {
+        /* libharu has no function to count the pages */
+        self.inner.page_count.get()
+    }
+
+    /// Get the page at `index` in the document order, counted from 0.
+    ///
+    /// Returns `Error::InvalidPageIndex` if the document has no page at `index`.
+    pub fn page(&self, index: usize) -> anyhow::Result<Page<'_>> {
+        let hpdf_index: libharu_sys::HPDF_UINT = match index.try_into() {
+            Ok(index) => index,
+            Err(_) => return Err(Error::PageOutOfRange.into()),
+        };
+
+        /* check here, so that the document is not left in the error state */
+        if index >= self.page_count() {
+            return Err(Error::InvalidPageIndex.into());
+        }
+
+        let page = unsafe {
+            ffi::HPDF_GetPageByIndex(self.handle(), hpdf_index)
+        };
+
+        if page.is_null() {
+            return Err(self.failure("HPDF_GetPageByIndex"));
+        }
+
         Ok(Page::new(self, page))
     }
 
+    /// Get the iterator over the pages in the document order, including inserted pages.
+    ///
+    /// Pages added during the iteration are not visited. Each item is the result of `page`.
+    pub fn pages(&self) -> PageIter<'_> {
+        PageIter { doc: self, index: 0, count: self.page_count() }
+    }
+
     /// Return the current page object.
-    pub fn current_page(&self) -> anyhow::Result<Page> {
+    pub fn current_page(&self) -> anyhow::Result<Page<'_>> {
         let page = unsafe {
             libharu_sys::HPDF_GetCurrentPage(self.handle())
         };
@@ -161,6 +562,7 @@ impl Document {
             PageMode::Outline => libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_USE_OUTLINE,
             PageMode::Thumbs => libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_USE_THUMBS,
             PageMode::FullScreen => libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_FULL_SCREEN,
+            PageMode::Attachments => return self.set_page_mode_attachments(),
         };
 
         let status = unsafe {
@@ -174,17 +576,34 @@ impl Document {
         Ok(())
     }
 
+    /// libharu rejects the modes after FullScreen, so write the entry directly.
+    fn set_page_mode_attachments(&self) -> anyhow::Result<()> {
+        let catalog = unsafe { ffi::doc_catalog(self.handle())? };
+
+        let status = unsafe {
+            ffi::HPDF_Dict_AddName(catalog, CString::new("PageMode")?.as_ptr(), CString::new("UseAttachments")?.as_ptr())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_AddName failed (status={})", status);
+        }
+
+        unsafe { ffi::require_version(self.handle(), ffi::HPDF_VER_16) }
+    }
+
     /// Get how the document should be displayed.
     pub fn page_mode(&self) -> anyhow::Result<PageMode> {
         let mode = unsafe {
-            libharu_sys::HPDF_GetPageMode(self.handle())
+            ffi::HPDF_GetPageMode(self.handle())
         };
 
+        /* the index in the page mode names of libharu, where UseOC comes before UseAttachments */
         let mode = match mode {
-            libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_USE_NONE => PageMode::None,
-            libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_USE_OUTLINE => PageMode::Outline,
-            libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_USE_THUMBS => PageMode::Thumbs,
-            libharu_sys::HPDF_PageMode::HPDF_PAGE_MODE_FULL_SCREEN => PageMode::FullScreen,
+            0 => PageMode::None,
+            1 => PageMode::Outline,
+            2 => PageMode::Thumbs,
+            3 => PageMode::FullScreen,
+            5 => PageMode::Attachments,
             _ => {
                 anyhow::bail!("HPDF_GetPageMode failed");
             }
@@ -194,7 +613,7 @@ impl Document {
     }
 
     /// Create a new page and inserts it just before the specified page.
-    pub fn insert_page(&self, target: &Page) -> anyhow::Result<Page> {
+    pub fn insert_page(&self, target: &Page<'_>) -> anyhow::Result<Page<'_>> {
         let page = unsafe {
             libharu_sys::HPDF_InsertPage(self.handle(), target.handle())
         };
@@ -203,11 +622,19 @@ impl Document {
             anyhow::bail!("HPDF_InsertPage failed");
         }
 
+        self.inner.page_count.set(self.inner.page_count.get() + 1);
+
         Ok(Page::new(self, page))
     }
 
     /// Gets the handle of a corresponding font object by specified name and encoding.
-    pub fn font(&self, font_name: &str, encoding_name: Option<&str>) -> anyhow::Result<Font> {
+    ///
+    /// In PDF/A mode, only fonts loaded with embedding are accepted.
+    pub fn font(&self, font_name: &str, encoding_name: Option<&str>) -> anyhow::Result<Font<'_>> {
+        if self.conformance() != Conformance::None && !self.inner.embedded_fonts.borrow().iter().any(|n| n == font_name) {
+            return Err(ConformanceError::NonEmbeddedFont(font_name.to_string()).into());
+        }
+
         let font_name = CString::new(font_name)?;
         let encoding_name = match encoding_name {
             Some(s) => Some(CString::new(s)?),
@@ -224,7 +651,7 @@ impl Document {
         };
 
         if font == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_GetFont failed");
+            return Err(self.failure("HPDF_GetFont"));
         }
 
         Ok(Font::new(self, font))
@@ -440,6 +867,8 @@ impl Document {
 
     /// Save the current document to a file.
     pub fn save_to_file(&self, name: &str) -> anyhow::Result<()> {
+        self.prepare_save()?;
+
         let name = CString::new(name).unwrap();
         let status = unsafe {
             libharu_sys::HPDF_SaveToFile(self.handle(), std::mem::transmute(name.as_bytes().as_ptr()))
@@ -452,116 +881,425 @@ impl Document {
         Ok(())
     }
 
-    /// Set the mode of compression.
-    pub fn set_compression_mode(&self, mode: CompressionMode) -> anyhow::Result<()> {
-        let status = unsafe {
-            libharu_sys::HPDF_SetCompressionMode(self.handle(), mode.bits())
-        };
+    /// Save the document into a writer.
+    pub fn save_to_writer<W: std::io::Write>(&self, writer: &mut W) -> anyhow::Result<()> {
+        self.save_to_stream()?;
 
-        if status != 0 {
-            anyhow::bail!("HPDF_SetCompressionMode failed (status = {})", status);
+        let mut buf = vec![0; SAVE_CHUNK_SIZE];
+        loop {
+            let len = self.read_stream(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            writer.write_all(&buf[..len])?;
         }
 
         Ok(())
     }
 
-    /// creates root outline object.
-    pub fn create_outline(&self, title: &str, parent: Option<&Outline>, enc: Option<&Encoder>) -> anyhow::Result<Outline> {
-        let title = CString::new(title)?;
-        
-        let outline = unsafe {
-            libharu_sys::HPDF_CreateOutline(
-                self.handle(),
-                match parent {
-                    Some(p) => p.handle(),
-                    None => std::ptr::null_mut(),
-                },
-                title.as_ptr() as *const i8,
-                match enc {
-                    Some(e) => e.handle(),
-                    None => std::ptr::null_mut(),
-                }
-            )
+    /// Save the document into a byte vector.
+    pub fn save_to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        self.save_to_stream()?;
+
+        let size = unsafe {
+            ffi::HPDF_GetStreamSize(self.handle())
         };
 
-        if outline == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_CreateOutline failed");
+        let mut data = vec![0; size as usize];
+        let mut len = 0;
+        while len < data.len() {
+            let read = self.read_stream(&mut data[len..])?;
+            if read == 0 {
+                break;
+            }
+            len += read;
         }
+        data.truncate(len);
 
-        Ok(Outline::new(self, outline))
+        Ok(data)
     }
 
-    /// creates root outline object. (raw bytes)
-    pub fn create_outline_bytes(&self, title: &[u8], parent: Option<&Outline>, enc: Option<&Encoder>) -> anyhow::Result<Outline> {
-        let title = CString::new(title)?;
-        
-        let outline = unsafe {
-            libharu_sys::HPDF_CreateOutline(
-                self.handle(),
-                match parent {
-                    Some(p) => p.handle(),
-                    None => std::ptr::null_mut(),
-                },
-                title.as_ptr() as *const i8,
-                match enc {
-                    Some(e) => e.handle(),
-                    None => std::ptr::null_mut(),
-                }
-            )
-        };
+    /// Save the document into a tokio writer.
+    ///
+    /// The document is rendered in memory first, then copied to the writer in chunks. Only the
+    /// copy is asynchronous: rendering blocks the executor thread like `save_to_bytes` does, so
+    /// render large documents with `save_to_bytes` in `tokio::task::spawn_blocking` instead.
+    ///
+    /// Takes `&mut self` because `Document` is `Send` but not `Sync`: the returned future holds
+    /// the reference across `.await`, and only `&mut Document` keeps it `Send` for `tokio::spawn`.
+    #[cfg(feature = "tokio")]
+    pub async fn save_to_async_writer<W>(&mut self, writer: &mut W) -> anyhow::Result<()>
+    where
+        W: tokio::io::AsyncWrite + Unpin
+    {
+        use tokio::io::AsyncWriteExt;
 
-        if outline == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_CreateOutline failed");
+        self.save_to_stream()?;
+
+        let mut buf = vec![0; SAVE_CHUNK_SIZE];
+        loop {
+            let len = self.read_stream(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            writer.write_all(&buf[..len]).await?;
         }
+        writer.flush().await?;
 
-        Ok(Outline::new(self, outline))
+        Ok(())
     }
 
-    /// Get the handle of a corresponding encoder object by specified encoding name.
-    pub fn find_encoder(&self, encoding_name: &str) -> anyhow::Result<Encoder> {
-        let encoding_name = CString::new(encoding_name)?;
-        let enc = unsafe {
-            libharu_sys::HPDF_GetEncoder(self.handle(), encoding_name.as_ptr())
+    /// Save the document into a futures writer.
+    ///
+    /// The document is rendered in memory first, then copied to the writer in chunks. Only the
+    /// copy is asynchronous: rendering blocks the executor thread like `save_to_bytes` does.
+    /// See `save_to_async_writer` for why this takes `&mut self`.
+    #[cfg(feature = "futures")]
+    pub async fn save_to_futures_writer<W>(&mut self, writer: &mut W) -> anyhow::Result<()>
+    where
+        W: futures_util::io::AsyncWrite + Unpin
+    {
+        use futures_util::io::AsyncWriteExt;
+
+        self.save_to_stream()?;
+
+        let mut buf = vec![0; SAVE_CHUNK_SIZE];
+        loop {
+            let len = self.read_stream(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            writer.write_all(&buf[..len]).await?;
+        }
+        writer.flush().await?;
+
+        Ok(())
+    }
+
+    /// Save the document into the internal memory stream and rewind it.
+    fn save_to_stream(&self) -> anyhow::Result<()> {
+        self.prepare_save()?;
+
+        let status = unsafe {
+            ffi::HPDF_SaveToStream(self.handle())
         };
 
-        if enc == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_GetEncoder failed");
+        if status != 0 {
+            anyhow::bail!("HPDF_SaveToStream failed (status={})", status);
+        }
+
+        let status = unsafe {
+            ffi::HPDF_ResetStream(self.handle())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_ResetStream failed (status={})", status);
         }
 
-        Ok(Encoder::new(self, enc))
+        Ok(())
     }
 
-    /// Get the handle of the current encoder of the document object.
-    pub fn current_encoder(&self) -> anyhow::Result<Encoder> {
-        let enc = unsafe {
-            libharu_sys::HPDF_GetCurrentEncoder(self.handle())
+    /// Read the next chunk of the internal memory stream. Returns 0 at the end of the stream.
+    fn read_stream(&self, buf: &mut [u8]) -> anyhow::Result<usize> {
+        let mut size = buf.len().try_into()?;
+        let status = unsafe {
+            ffi::HPDF_ReadFromStream(self.handle(), buf.as_mut_ptr(), &mut size)
         };
 
-        if enc == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_GetCurrentEncoder failed");
+        /* HPDF_STREAM_EOF is returned with the last chunk */
+        if status != 0 && !matches!(Error::from_num(status), Error::StreamEof) {
+            anyhow::bail!("HPDF_ReadFromStream failed (status={})", status);
         }
 
-        Ok(Encoder::new(self, enc))
+        Ok(size as usize)
     }
 
-    /// Set the handle of the current encoder of the document object.
-    pub fn set_current_encoder(&self, encoding_name: &str) -> anyhow::Result<()> {
-        let encoding_name = CString::new(encoding_name)?;
+    /// Set the mode of compression.
+    pub fn set_compression_mode(&self, mode: CompressionMode) -> anyhow::Result<()> {
         let status = unsafe {
-            libharu_sys::HPDF_SetCurrentEncoder(self.handle(), encoding_name.as_ptr())
+            libharu_sys::HPDF_SetCompressionMode(self.handle(), mode.bits())
         };
 
         if status != 0 {
-            anyhow::bail!("HPDF_SetCurrentEncoder failed (status={})", status);
+            anyhow::bail!("HPDF_SetCompressionMode failed (status = {})", status);
         }
 
         Ok(())
     }
-    
-    /// Get the current setting for page layout.
-    pub fn page_layout(&self) -> anyhow::Result<PageLayout> {
-        let layout = unsafe {
-            libharu_sys::HPDF_GetPageLayout(self.handle())
+
+    /// Set how the viewer application should present the document.
+    pub fn set_viewer_preferences(&self, prefs: ViewerPreferences) -> anyhow::Result<()> {
+        let print_scaling_none = prefs.contains(ViewerPreferences::PRINT_SCALING_NONE);
+        if print_scaling_none {
+            unsafe { ffi::require_version(self.handle(), ffi::HPDF_VER_16)? };
+        }
+
+        let status = unsafe {
+            libharu_sys::HPDF_SetViewerPreference(self.handle(), prefs.bits())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_SetViewerPreference failed (status={})", status);
+        }
+
+        self.inner.print_scaling_none.set(print_scaling_none);
+
+        Ok(())
+    }
+
+    /// Get how the viewer application should present the document.
+    pub fn viewer_preferences(&self) -> anyhow::Result<ViewerPreferences> {
+        let bits = unsafe {
+            libharu_sys::HPDF_GetViewerPreference(self.handle())
+        };
+
+        let mut prefs = ViewerPreferences::from_bits_truncate(bits);
+
+        /* HPDF_GetViewerPreference does not report PrintScaling */
+        if self.inner.print_scaling_none.get() {
+            prefs |= ViewerPreferences::PRINT_SCALING_NONE;
+        }
+
+        Ok(prefs)
+    }
+
+    /// Set the attribute of the info dictionary.
+    ///
+    /// Non-ASCII values, and any value while a current encoder is set, require the
+    /// `bundled-internals` feature.
+    pub fn set_info_attr(&self, type_: InfoType, value: &str) -> anyhow::Result<()> {
+        if type_.is_date() {
+            anyhow::bail!("{:?} is a date attribute; use set_info_date_attr", type_);
+        }
+
+        let status = unsafe {
+            if value.is_ascii() && self.inner.current_encoder.borrow().is_none() {
+                let value = CString::new(value)?;
+                ffi::HPDF_SetInfoAttr(self.handle(), type_.to_sys(), value.as_ptr())
+            } else {
+                /* HPDF_SetInfoAttr converts the value by the current encoder; write UTF-16 instead */
+                let info = ffi::doc_info(self.handle())?;
+                if info.is_null() {
+                    anyhow::bail!("document has no info dictionary");
+                }
+
+                let key = CString::new(type_.key())?;
+                let value = ffi::text_string_new(ffi::doc_mmgr(self.handle())?, value)?;
+                ffi::HPDF_Dict_Add(info, key.as_ptr(), value)
+            }
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_SetInfoAttr failed (status={})", status);
+        }
+
+        self.inner.info.borrow_mut().text.insert(type_, value.to_string());
+
+        Ok(())
+    }
+
+    /// Get the attribute of the info dictionary.
+    pub fn info_attr(&self, type_: InfoType) -> Option<String> {
+        self.inner.info.borrow().text.get(&type_).cloned()
+    }
+
+    /// Set the date attribute (`InfoType::CreationDate` or `InfoType::ModDate`) of the info dictionary.
+    pub fn set_info_date_attr(&self, type_: InfoType, value: InfoDate) -> anyhow::Result<()> {
+        if !type_.is_date() {
+            anyhow::bail!("{:?} is not a date attribute; use set_info_attr", type_);
+        }
+
+        let status = unsafe {
+            ffi::HPDF_SetInfoDateAttr(self.handle(), type_.to_sys(), value.to_ffi())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_SetInfoDateAttr failed (status={})", status);
+        }
+
+        self.inner.info.borrow_mut().dates.insert(type_, value);
+
+        Ok(())
+    }
+
+    /// Get the date attribute of the info dictionary.
+    pub fn info_date_attr(&self, type_: InfoType) -> Option<InfoDate> {
+        self.inner.info.borrow().dates.get(&type_).copied()
+    }
+
+    /// Set the conformance level of the document.
+    ///
+    /// With `Conformance::PdfA1B`, the XMP metadata (by `HPDF_PDFA_SetPDFAConformance`) and an sRGB
+    /// output intent, unless one is added by `add_output_intent`, are generated when the document
+    /// is saved. Fonts loaded by `load_ttf_font` are always embedded, and operations which break the
+    /// conformance (embedded files, 3D artwork) fail with `ConformanceError`.
+    ///
+    /// Transparency is checked only for PNG files loaded by `load_png_image` and the opacity of
+    /// markup annotations; the caller is responsible for the other contents.
+    ///
+    /// Conformance levels other than `Conformance::None` require the `bundled-internals` feature.
+    pub fn set_conformance(&self, conformance: Conformance) -> anyhow::Result<()> {
+        if conformance != Conformance::None {
+            ffi::require_internals()?;
+        }
+
+        self.inner.conformance.set(conformance);
+
+        Ok(())
+    }
+
+    /// Attach XMP metadata to the catalog. The packet is rendered when the document is saved.
+    ///
+    /// Requires the `bundled-internals` feature; without it, saving the document fails.
+    pub fn set_xmp_metadata(&self, xmp: XmpMetadata) {
+        *self.inner.xmp.borrow_mut() = Some(xmp);
+    }
+
+    /// Get the conformance level of the document.
+    pub fn conformance(&self) -> Conformance {
+        self.inner.conformance.get()
+    }
+
+    /// creates root outline object.
+    ///
+    /// A `&str` title is converted into the encoding of the encoder like `show_text` (e.g. into
+    /// Shift_JIS for the "90ms-RKSJ-H" encoder with the `encoding` feature), and bytes are passed
+    /// to libharu as is. libharu converts the title by the encoder if it is specified.
+    pub fn create_outline<'t, T>(&self, title: T, parent: Option<&Outline<'_>>, enc: Option<&Encoder<'_>>) -> anyhow::Result<Outline<'_>>
+    where
+        T: Into<OutlineTitle<'t>>
+    {
+        let title = CString::new(title.into().encode(enc)?.into_owned())?;
+        
+        let outline = unsafe {
+            libharu_sys::HPDF_CreateOutline(
+                self.handle(),
+                match parent {
+                    Some(p) => p.handle(),
+                    None => std::ptr::null_mut(),
+                },
+                title.as_ptr() as *const i8,
+                match enc {
+                    Some(e) => e.handle(),
+                    None => std::ptr::null_mut(),
+                }
+            )
+        };
+
+        if outline == std::ptr::null_mut() {
+            anyhow::bail!("HPDF_CreateOutline failed");
+        }
+
+        Ok(Outline::new(self, outline))
+    }
+
+    /// creates root outline object. (raw bytes)
+    #[deprecated(note = "use `Document::create_outline`, which accepts raw bytes")]
+    pub fn create_outline_bytes(&self, title: &[u8], parent: Option<&Outline<'_>>, enc: Option<&Encoder<'_>>) -> anyhow::Result<Outline<'_>> {
+        self.create_outline(title, parent, enc)
+    }
+
+    /// Create outline objects from the outline tree in one operation.
+    ///
+    /// The titles are converted by the encoder if it is specified. All destinations in the tree
+    /// must belong to this document.
+    pub fn create_outline_tree(&self, tree: &OutlineTree, enc: Option<&Encoder>) -> anyhow::Result<()> {
+        for (_, node) in tree.iter() {
+            if let Some((doc, _)) = node.dst {
+                if !std::ptr::eq(doc, self) {
+                    anyhow::bail!("destination of outline \"{}\" belongs to another document", String::from_utf8_lossy(node.title()));
+                }
+            }
+        }
+
+        for node in tree.nodes() {
+            self.create_outline_node(node, None, enc)?;
+        }
+
+        Ok(())
+    }
+
+    fn create_outline_node(&self, node: &OutlineNode, parent: Option<&Outline>, enc: Option<&Encoder>) -> anyhow::Result<()> {
+        let outline = self.create_outline(node.title.clone(), parent, enc)?;
+
+        if let Some((_, dst)) = node.dst {
+            let status = unsafe {
+                libharu_sys::HPDF_Outline_SetDestination(outline.handle(), dst)
+            };
+
+            if status != 0 {
+                anyhow::bail!("HPDF_Outline_SetDestination failed (status={})", status);
+            }
+        }
+
+        outline.set_opened(node.is_opened())?;
+
+        if let Some(color) = node.color() {
+            outline.set_color(color)?;
+        }
+
+        if !node.style().is_empty() {
+            outline.set_style(node.style())?;
+        }
+
+        for child in node.children() {
+            self.create_outline_node(child, Some(&outline), enc)?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the handle of a corresponding encoder object by specified encoding name.
+    pub fn find_encoder(&self, encoding_name: &str) -> anyhow::Result<Encoder<'_>> {
+        let name = CString::new(encoding_name)?;
+        let enc = unsafe {
+            libharu_sys::HPDF_GetEncoder(self.handle(), name.as_ptr())
+        };
+
+        if enc == std::ptr::null_mut() {
+            return Err(self.failure("HPDF_GetEncoder"));
+        }
+
+        /* libharu finds the encoder by the exact name */
+        Ok(Encoder::new(self, enc, encoding_name))
+    }
+
+    /// Get the handle of the current encoder of the document object.
+    pub fn current_encoder(&self) -> anyhow::Result<Encoder<'_>> {
+        let enc = unsafe {
+            libharu_sys::HPDF_GetCurrentEncoder(self.handle())
+        };
+
+        let name = self.inner.current_encoder.borrow();
+        let name = match (enc.is_null(), name.as_deref()) {
+            (false, Some(name)) => name,
+            _ => anyhow::bail!("HPDF_GetCurrentEncoder failed"),
+        };
+
+        Ok(Encoder::new(self, enc, name))
+    }
+
+    /// Set the handle of the current encoder of the document object.
+    pub fn set_current_encoder(&self, encoding_name: &str) -> anyhow::Result<()> {
+        let name = CString::new(encoding_name)?;
+        let status = unsafe {
+            libharu_sys::HPDF_SetCurrentEncoder(self.handle(), name.as_ptr())
+        };
+
+        if status != 0 {
+            return Err(self.failure("HPDF_SetCurrentEncoder"));
+        }
+
+        /* libharu has no function to read the name of an encoder */
+        *self.inner.current_encoder.borrow_mut() = Some(encoding_name.to_string());
+
+        Ok(())
+    }
+    
+    /// Get the current setting for page layout.
+    pub fn page_layout(&self) -> anyhow::Result<PageLayout> {
+        let layout = unsafe {
+            libharu_sys::HPDF_GetPageLayout(self.handle())
         };
 
         Ok(match layout {
@@ -569,6 +1307,8 @@ impl Document {
             libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_ONE_COLUMN => PageLayout::OneColumn,
             libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_COLUMN_LEFT => PageLayout::TwoColumnLeft,
             libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_COLUMN_RIGHT  => PageLayout::TwoColumnRight,
+            libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_PAGE_LEFT => PageLayout::TwoPageLeft,
+            libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_PAGE_RIGHT => PageLayout::TwoPageRight,
             _ => anyhow::bail!("HPDF_GetPageLayout failed"),
         })
     }
@@ -580,6 +1320,8 @@ impl Document {
             PageLayout::OneColumn => libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_ONE_COLUMN,
             PageLayout::TwoColumnLeft => libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_COLUMN_LEFT,
             PageLayout::TwoColumnRight  => libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_COLUMN_RIGHT,
+            PageLayout::TwoPageLeft => libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_PAGE_LEFT,
+            PageLayout::TwoPageRight => libharu_sys::HPDF_PageLayout::HPDF_PAGE_LAYOUT_TWO_PAGE_RIGHT,
         };
 
         let status = unsafe {
@@ -594,24 +1336,34 @@ impl Document {
     }
 
     /// load a TrueType font from an external file and register it to a document object.
+    ///
+    /// In PDF/A mode, the font is always embedded.
     pub fn load_ttf_font(&self, name: &str, embedding: bool) -> anyhow::Result<&str> {
+        let embedding = embedding || self.conformance() != Conformance::None;
         let name = CString::new(name)?;
         let ret = unsafe {
             libharu_sys::HPDF_LoadTTFontFromFile(self.handle(), name.as_ptr(), if embedding { 1 } else { 0 } )
         };
 
         if ret == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_LoadTTFontFromFile failed");
+            return Err(self.failure("HPDF_LoadTTFontFromFile"));
         }
         
         let s = unsafe { std::ffi::CStr::from_ptr(ret).to_str()? };
 
+        if embedding {
+            self.inner.embedded_fonts.borrow_mut().push(s.to_string());
+        }
+
         //let ret = unsafe { CString::from_raw(ret as *mut i8).into_string()? };
         Ok(s)
     }
 
     /// Load a TrueType font from an TrueType collection file and register it to a document object.
+    ///
+    /// In PDF/A mode, the font is always embedded.
     pub fn load_ttf_font_from_ttc(&self, name: &str, index: usize, embedding: bool) -> anyhow::Result<&str> {
+        let embedding = embedding || self.conformance() != Conformance::None;
         let name = CString::new(name)?;
         let index = index as u32;
         
@@ -620,17 +1372,27 @@ impl Document {
         };
 
         if ret == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_LoadTTFontFromFile failed");
+            return Err(self.failure("HPDF_LoadTTFontFromFile2"));
         }
         
         let s = unsafe { std::ffi::CStr::from_ptr(ret).to_str()? };
 
+        if embedding {
+            self.inner.embedded_fonts.borrow_mut().push(s.to_string());
+        }
+
         //let ret = unsafe { CString::from_raw(ret as *mut i8).into_string()? };
         Ok(s)
     }
 
     /// Load an external png image file.
-    pub fn load_png_image(&self, name: &str) -> anyhow::Result<Image> {
+    ///
+    /// In PDF/A-1 mode, images with an alpha channel are rejected.
+    pub fn load_png_image(&self, name: &str) -> anyhow::Result<Image<'_>> {
+        if self.conformance() == Conformance::PdfA1B && crate::image::png_has_alpha(&std::fs::read(name)?) {
+            return Err(ConformanceError::Transparency.into());
+        }
+
         let name = CString::new(name)?;
 
         let image = unsafe {
@@ -638,12 +1400,242 @@ impl Document {
         };
 
         if image == std::ptr::null_mut() {
-            anyhow::bail!("HPDF_LoadPngImageFromFile failed");
+            return Err(self.failure("HPDF_LoadPngImageFromFile"));
         }
 
         Ok(Image::new(self, image))
     }
 
+    /// Load an external U3D or PRC file as 3D artwork.
+    ///
+    /// The document is written as PDF 1.7. PDF/A-1 does not allow 3D artwork.
+    pub fn load_u3d(&self, name: &str) -> anyhow::Result<U3D<'_>> {
+        if self.conformance() == Conformance::PdfA1B {
+            return Err(ConformanceError::Artwork3D.into());
+        }
+
+        let mut header = Vec::with_capacity(4);
+        std::fs::File::open(name)?.take(4).read_to_end(&mut header)?;
+        crate::u3d::validate_data(&header)?;
+
+        let name = CString::new(name)?;
+
+        let u3d = unsafe {
+            libharu_sys::HPDF_LoadU3DFromFile(self.handle(), name.as_ptr())
+        };
+
+        if u3d.is_null() {
+            return Err(self.failure("HPDF_LoadU3DFromFile"));
+        }
+
+        Ok(U3D::new(self, u3d))
+    }
+
+    /// Load U3D or PRC data on memory as 3D artwork.
+    ///
+    /// The document is written as PDF 1.7. PDF/A-1 does not allow 3D artwork.
+    ///
+    /// Requires the `bundled-internals` feature, which raises the version; use `load_u3d` without it.
+    pub fn load_u3d_from_mem(&self, data: &[u8]) -> anyhow::Result<U3D<'_>> {
+        if self.conformance() == Conformance::PdfA1B {
+            return Err(ConformanceError::Artwork3D.into());
+        }
+
+        crate::u3d::validate_data(data)?;
+
+        /* unlike HPDF_LoadU3DFromFile, libharu leaves the version as is */
+        unsafe { ffi::require_version(self.handle(), ffi::HPDF_VER_17)? };
+
+        let u3d = unsafe {
+            libharu_sys::HPDF_LoadU3DFromMem(self.handle(), data.as_ptr(), data.len().try_into()?)
+        };
+
+        if u3d.is_null() {
+            return Err(self.failure("HPDF_LoadU3DFromMem"));
+        }
+
+        Ok(U3D::new(self, u3d))
+    }
+
+    /// Load an ICC profile as a color space with the given number of components (1, 3 or 4).
+    ///
+    /// Requires the `bundled-internals` feature.
+    pub fn load_icc_profile(&self, data: &[u8], components: usize) -> anyhow::Result<IccProfile<'_>> {
+        let alternate = match components {
+            1 => "DeviceGray",
+            3 => "DeviceRGB",
+            4 => "DeviceCMYK",
+            _ => return Err(Error::InvalidIccComponentNum.into()),
+        };
+
+        crate::icc::validate_profile(data, components)?;
+
+        let color_space = unsafe {
+            let (mmgr, xref) = (ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?);
+            let icc = ffi::HPDF_DictStream_New(mmgr, xref);
+            let color_space = ffi::HPDF_Array_New(mmgr);
+            if icc.is_null() || color_space.is_null() {
+                anyhow::bail!("HPDF_DictStream_New failed");
+            }
+
+            ffi::set_stream_filter(icc, ffi::HPDF_STREAM_FILTER_FLATE_DECODE)?;
+            ffi::check(ffi::HPDF_Stream_Write(ffi::dict_stream(icc)?, data.as_ptr(), data.len().try_into()?), "HPDF_Stream_Write")?;
+            ffi::check(ffi::HPDF_Dict_AddNumber(icc, CString::new("N")?.as_ptr(), components as i32), "HPDF_Dict_AddNumber")?;
+            ffi::check(ffi::HPDF_Dict_AddName(icc, CString::new("Alternate")?.as_ptr(), CString::new(alternate)?.as_ptr()), "HPDF_Dict_AddName")?;
+
+            /* the color space is shared by pages and images */
+            ffi::check(ffi::HPDF_Xref_Add(xref, color_space), "HPDF_Xref_Add")?;
+            ffi::check(ffi::HPDF_Array_AddName(color_space, CString::new("ICCBased")?.as_ptr()), "HPDF_Array_AddName")?;
+            ffi::check(ffi::HPDF_Array_Add(color_space, icc), "HPDF_Array_Add")?;
+            color_space
+        };
+
+        Ok(IccProfile::new(self, color_space, components))
+    }
+
+    /// Load the built-in sRGB IEC61966-2.1 profile as a color space. Requires the `bundled-internals` feature.
+    pub fn load_srgb_icc_profile(&self) -> anyhow::Result<IccProfile<'_>> {
+        self.load_icc_profile(&crate::icc::srgb_profile(), 3)
+    }
+
+    /// Add an output intent with the ICC profile of the output condition to the catalog.
+    /// Requires the `bundled-internals` feature.
+    pub fn add_output_intent(&self, intent: &OutputIntent, profile: &IccProfile) -> anyhow::Result<()> {
+        let key = CString::new("OutputIntents")?;
+
+        unsafe {
+            let (mmgr, catalog) = (ffi::doc_mmgr(self.handle())?, ffi::doc_catalog(self.handle())?);
+            let dict = ffi::HPDF_Dict_New(mmgr);
+            if dict.is_null() {
+                anyhow::bail!("HPDF_Dict_New failed");
+            }
+
+            /* second element of [/ICCBased stream] */
+            let icc = ffi::HPDF_Array_GetItem(profile.handle(), 1, ffi::HPDF_OCLASS_DICT);
+
+            ffi::check(ffi::HPDF_Dict_AddName(dict, CString::new("Type")?.as_ptr(), CString::new("OutputIntent")?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_AddName(dict, CString::new("S")?.as_ptr(), CString::new(intent.subtype.name())?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_Add(dict, CString::new("OutputConditionIdentifier")?.as_ptr(), ffi::text_string_new(mmgr, &intent.identifier)?), "HPDF_Dict_Add")?;
+            if let Some(condition) = &intent.condition {
+                ffi::check(ffi::HPDF_Dict_Add(dict, CString::new("OutputCondition")?.as_ptr(), ffi::text_string_new(mmgr, condition)?), "HPDF_Dict_Add")?;
+            }
+            if let Some(registry) = &intent.registry {
+                ffi::check(ffi::HPDF_Dict_Add(dict, CString::new("RegistryName")?.as_ptr(), ffi::text_string_new(mmgr, registry)?), "HPDF_Dict_Add")?;
+            }
+            if let Some(info) = &intent.info {
+                ffi::check(ffi::HPDF_Dict_Add(dict, CString::new("Info")?.as_ptr(), ffi::text_string_new(mmgr, info)?), "HPDF_Dict_Add")?;
+            }
+            ffi::check(ffi::HPDF_Dict_Add(dict, CString::new("DestOutputProfile")?.as_ptr(), icc), "HPDF_Dict_Add")?;
+
+            let mut intents = ffi::HPDF_Dict_GetItem(catalog, key.as_ptr(), ffi::HPDF_OCLASS_ARRAY);
+            if intents.is_null() {
+                intents = ffi::HPDF_Array_New(mmgr);
+                if intents.is_null() {
+                    anyhow::bail!("HPDF_Array_New failed");
+                }
+                ffi::check(ffi::HPDF_Dict_Add(catalog, key.as_ptr(), intents), "HPDF_Dict_Add")?;
+            }
+            ffi::check(ffi::HPDF_Array_Add(intents, dict), "HPDF_Array_Add")?;
+        }
+
+        Ok(())
+    }
+
+    /// Record drawing commands once into a form XObject of the given size.
+    ///
+    /// The closure draws on a page which is not part of the document; the recorded
+    /// commands can then be placed on any page with `Page::execute_xobject`.
+    ///
+    /// Requires the `bundled-internals` feature.
+    pub fn create_form_xobject<F>(&self, width: Real, height: Real, f: F) -> anyhow::Result<XObject<'_>>
+    where
+        F: FnOnce(&PageDescriptionMode) -> anyhow::Result<()>
+    {
+        let (mmgr, xref) = unsafe { (ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?) };
+
+        /* the recorder page lives in a cross-reference table of its own, which is freed
+           with everything in it once the commands are copied into the form */
+        let recorder_xref = unsafe { ffi::HPDF_Xref_New(mmgr, 0) };
+        if recorder_xref.is_null() {
+            anyhow::bail!("HPDF_Xref_New failed");
+        }
+
+        let result = self.record_form_xobject(mmgr, xref, recorder_xref, width, height, f);
+        unsafe { ffi::HPDF_Xref_Free(recorder_xref) };
+
+        result
+    }
+
+    fn record_form_xobject<F>(&self, mmgr: libharu_sys::HPDF_MMgr, xref: libharu_sys::HPDF_Xref, recorder_xref: libharu_sys::HPDF_Xref, width: Real, height: Real, f: F) -> anyhow::Result<XObject<'_>>
+    where
+        F: FnOnce(&PageDescriptionMode) -> anyhow::Result<()>
+    {
+        let (page, resources) = unsafe {
+            let page = ffi::HPDF_Page_New(mmgr, recorder_xref);
+            if page.is_null() {
+                anyhow::bail!("HPDF_Page_New failed");
+            }
+
+            /* resources must be indirect, so that they outlive the recorder page */
+            let resources = ffi::HPDF_Dict_New(mmgr);
+            if resources.is_null() {
+                anyhow::bail!("HPDF_Dict_New failed");
+            }
+            ffi::check(ffi::HPDF_Xref_Add(xref, resources), "HPDF_Xref_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(page, CString::new("Resources")?.as_ptr(), resources), "HPDF_Dict_Add")?;
+
+            (page, resources)
+        };
+
+        let recorder = Page::new(self, page);
+        recorder.set_width(width)?;
+        recorder.set_height(height)?;
+        f(&PageDescriptionMode::new(&recorder))?;
+
+        unsafe {
+            if libharu_sys::HPDF_Page_GetGMode(page) != ffi::HPDF_GMODE_PAGE_DESCRIPTION || libharu_sys::HPDF_Page_GetGStateDepth(page) != 1 {
+                anyhow::bail!("form XObject recording left an unfinished text/path object or graphics state");
+            }
+
+            let form = ffi::HPDF_DictStream_New(mmgr, xref);
+            if form.is_null() {
+                anyhow::bail!("HPDF_DictStream_New failed");
+            }
+
+            let bbox = ffi::HPDF_Array_New(mmgr);
+            if bbox.is_null() {
+                anyhow::bail!("HPDF_Array_New failed");
+            }
+            for v in [0.0, 0.0, width, height] {
+                ffi::check(ffi::HPDF_Array_AddReal(bbox, v), "HPDF_Array_AddReal")?;
+            }
+
+            let matrix = ffi::HPDF_Array_New(mmgr);
+            if matrix.is_null() {
+                anyhow::bail!("HPDF_Array_New failed");
+            }
+            for v in [1.0, 0.0, 0.0, 1.0, 0.0, 0.0] {
+                ffi::check(ffi::HPDF_Array_AddReal(matrix, v), "HPDF_Array_AddReal")?;
+            }
+
+            ffi::check(ffi::HPDF_Dict_AddName(form, CString::new("Type")?.as_ptr(), CString::new("XObject")?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_AddName(form, CString::new("Subtype")?.as_ptr(), CString::new("Form")?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_Add(form, CString::new("BBox")?.as_ptr(), bbox), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(form, CString::new("Matrix")?.as_ptr(), matrix), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(form, CString::new("Resources")?.as_ptr(), resources), "HPDF_Dict_Add")?;
+
+            let status = ffi::HPDF_Stream_WriteToStream(ffi::page_stream(page)?, ffi::dict_stream(form)?, 0, std::ptr::null_mut());
+            ffi::check(status, "HPDF_Stream_WriteToStream")?;
+
+            if ffi::doc_compression_mode(self.handle())? & ffi::HPDF_COMP_TEXT != 0 {
+                ffi::set_stream_filter(form, ffi::HPDF_STREAM_FILTER_FLATE_DECODE)?;
+            }
+            ffi::set_xobject_class(form)?;
+
+            Ok(XObject::new(self, form, width, height))
+        }
+    }
+
     /// Set the first page appears when a document is opened.
     pub fn set_open_action(&self, dst: &Destination) -> anyhow::Result<()> {
         let status = unsafe {
@@ -656,6 +1648,339 @@ impl Document {
 
         Ok(())
     }
+
+    /// Set the named destination which appears when a document is opened.
+    ///
+    /// The name need not be registered by `add_named_destination` yet. Requires the
+    /// `bundled-internals` feature.
+    pub fn set_open_action_by_name(&self, name: &str) -> anyhow::Result<()> {
+        unsafe {
+            let (mmgr, catalog) = (ffi::doc_mmgr(self.handle())?, ffi::doc_catalog(self.handle())?);
+            let action = ffi::HPDF_Dict_New(mmgr);
+            if action.is_null() {
+                anyhow::bail!("HPDF_Dict_New failed");
+            }
+
+            ffi::check(ffi::HPDF_Dict_AddName(action, CString::new("Type")?.as_ptr(), CString::new("Action")?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_AddName(action, CString::new("S")?.as_ptr(), CString::new("GoTo")?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_Add(action, CString::new("D")?.as_ptr(), crate::destination::name_string_new(mmgr, name)?), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(catalog, CString::new("OpenAction")?.as_ptr(), action), "HPDF_Dict_Add")?;
+        }
+
+        Ok(())
+    }
+
+    /// Register the destination in the Dests name tree of the catalog, so that it can be targeted by `name`
+    /// from other documents.
+    ///
+    /// Names are limited to 127 bytes. Requires the `bundled-internals` feature.
+    pub fn add_named_destination(&self, name: &str, dst: &Destination) -> anyhow::Result<()> {
+        let mut names = self.inner.dest_names.borrow_mut();
+
+        /* name trees are sorted by the keys, which libharu fails to keep in the middle of the array */
+        let index = match names.binary_search_by(|n| n.as_str().cmp(name)) {
+            Ok(_) => anyhow::bail!("named destination \"{}\" already exists", name),
+            Err(index) => index,
+        };
+
+        unsafe {
+            let mmgr = ffi::doc_mmgr(self.handle())?;
+            let key = crate::destination::name_string_new(mmgr, name)?;
+            let items = ffi::HPDF_Dict_GetItem(self.dests_tree()?, CString::new("Names")?.as_ptr(), ffi::HPDF_OCLASS_ARRAY);
+            if items.is_null() {
+                anyhow::bail!("HPDF_Dict_GetItem failed");
+            }
+
+            /* HPDF_Array_Insert wraps an indirect object such as a destination in a proxy, and then
+             * looks for the target by the class of the inserted object instead of each item, so it
+             * never finds the target. Insert the destination wrapped in << /D dst >>, which is direct. */
+            let value = ffi::HPDF_Dict_New(mmgr);
+            if value.is_null() {
+                anyhow::bail!("HPDF_Dict_New failed");
+            }
+
+            ffi::check(ffi::HPDF_Dict_Add(value, CString::new("D")?.as_ptr(), dst.handle()), "HPDF_Dict_Add")?;
+            if index == names.len() {
+                ffi::check(ffi::HPDF_Array_Add(items, key), "HPDF_Array_Add")?;
+                ffi::check(ffi::HPDF_Array_Add(items, value), "HPDF_Array_Add")?;
+            } else {
+                let target = ffi::HPDF_Array_GetItem(items, (index * 2).try_into()?, ffi::HPDF_OCLASS_STRING);
+                ffi::check(ffi::HPDF_Array_Insert(items, target, key), "HPDF_Array_Insert")?;
+                ffi::check(ffi::HPDF_Array_Insert(items, target, value), "HPDF_Array_Insert")?;
+            }
+        }
+
+        names.insert(index, name.to_string());
+
+        Ok(())
+    }
+
+    /// Embed the file into the document as an attachment, named by the file name of the path.
+    ///
+    /// PDF/A-1 does not allow embedded files. Requires the `bundled-internals` feature.
+    pub fn attach_file<P>(&self, path: P) -> anyhow::Result<EmbeddedFile<'_>>
+    where
+        P: AsRef<std::path::Path>
+    {
+        let path = path.as_ref();
+
+        /* HPDF_AttachFile names the attachment by the whole path */
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) => name,
+            None => anyhow::bail!("invalid file name ({})", path.display()),
+        };
+
+        self.attach_file_from_mem(name, None, None, &std::fs::read(path)?)
+    }
+
+    /// Embed the bytes into the document as an attachment named `name`.
+    ///
+    /// PDF/A-1 does not allow embedded files. Requires the `bundled-internals` feature.
+    pub fn attach_file_from_mem(&self, name: &str, mime_type: Option<&str>, description: Option<&str>, data: &[u8]) -> anyhow::Result<EmbeddedFile<'_>> {
+        if self.conformance() == Conformance::PdfA1B {
+            return Err(ConformanceError::EmbeddedFile.into());
+        }
+
+        let efile = unsafe {
+            let (mmgr, xref) = (ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?);
+            let tree = self.embedded_files_tree()?;
+
+            let efile = ffi::HPDF_Dict_New(mmgr);
+            if efile.is_null() {
+                anyhow::bail!("HPDF_Dict_New failed");
+            }
+
+            ffi::check(ffi::HPDF_Xref_Add(xref, efile), "HPDF_Xref_Add")?;
+
+            let stream = ffi::HPDF_DictStream_New(mmgr, xref);
+            let ef = ffi::HPDF_Dict_New(mmgr);
+            if stream.is_null() || ef.is_null() {
+                anyhow::bail!("HPDF_Dict_New failed");
+            }
+
+            ffi::set_stream_filter(stream, ffi::HPDF_STREAM_FILTER_FLATE_DECODE)?;
+            ffi::check(ffi::HPDF_Stream_Write(ffi::dict_stream(stream)?, data.as_ptr(), data.len().try_into()?), "HPDF_Stream_Write")?;
+
+            ffi::check(ffi::HPDF_Dict_AddName(stream, CString::new("Type")?.as_ptr(), CString::new("EmbeddedFile")?.as_ptr()), "HPDF_Dict_AddName")?;
+            ffi::check(ffi::HPDF_Dict_AddName(efile, CString::new("Type")?.as_ptr(), CString::new("Filespec")?.as_ptr()), "HPDF_Dict_AddName")?;
+            /* name tree keys and "F" are byte strings; "UF" is a text string */
+            let cname = CString::new(name)?;
+            let key = ffi::HPDF_String_New(mmgr, cname.as_ptr(), std::ptr::null_mut());
+            let file_name = ffi::HPDF_String_New(mmgr, cname.as_ptr(), std::ptr::null_mut());
+            if key.is_null() || file_name.is_null() {
+                anyhow::bail!("HPDF_String_New failed");
+            }
+
+            ffi::check(ffi::HPDF_Dict_Add(efile, CString::new("F")?.as_ptr(), file_name), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(efile, CString::new("UF")?.as_ptr(), ffi::text_string_new(mmgr, name)?), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(efile, CString::new("EF")?.as_ptr(), ef), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_Dict_Add(ef, CString::new("F")?.as_ptr(), stream), "HPDF_Dict_Add")?;
+            ffi::check(ffi::HPDF_NameTree_Add(tree, key, efile), "HPDF_NameTree_Add")?;
+
+            EmbeddedFile::new(self, efile)
+        };
+
+        if let Some(mime_type) = mime_type {
+            efile.set_mime_type(mime_type)?;
+        }
+
+        if let Some(description) = description {
+            efile.set_description(description)?;
+        }
+
+        Ok(efile)
+    }
+
+    /// Get the Names dictionary of the catalog, creating it if necessary.
+    unsafe fn names_dict(&self) -> anyhow::Result<libharu_sys::HPDF_Dict> {
+        let catalog = ffi::doc_catalog(self.handle())?;
+
+        let mut names = ffi::HPDF_Catalog_GetNames(catalog);
+        if names.is_null() {
+            names = ffi::HPDF_NameDict_New(ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?);
+            if names.is_null() || ffi::HPDF_Catalog_SetNames(catalog, names) != 0 {
+                return Err(Error::NameCannotGetNames.into());
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Get the Dests name tree of the catalog, creating it if necessary.
+    unsafe fn dests_tree(&self) -> anyhow::Result<libharu_sys::HPDF_Dict> {
+        let names = self.names_dict()?;
+        let key = CString::new("Dests")?;
+
+        /* libharu knows only the EmbeddedFiles tree */
+        let mut tree = ffi::HPDF_Dict_GetItem(names, key.as_ptr(), ffi::HPDF_OCLASS_DICT);
+        if tree.is_null() {
+            tree = ffi::HPDF_NameTree_New(ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?);
+            if tree.is_null() {
+                anyhow::bail!("HPDF_NameTree_New failed");
+            }
+
+            let status = ffi::HPDF_Dict_Add(names, key.as_ptr(), tree);
+            if status != 0 {
+                anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Get the EmbeddedFiles name tree of the catalog, creating it if necessary.
+    unsafe fn embedded_files_tree(&self) -> anyhow::Result<libharu_sys::HPDF_Dict> {
+        let names = self.names_dict()?;
+
+        let mut tree = ffi::HPDF_NameDict_GetNameTree(names, ffi::HPDF_NAME_EMBEDDED_FILES);
+        if tree.is_null() {
+            tree = ffi::HPDF_NameTree_New(ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?);
+            if tree.is_null() {
+                anyhow::bail!("HPDF_NameTree_New failed");
+            }
+
+            let status = ffi::HPDF_NameDict_SetNameTree(names, ffi::HPDF_NAME_EMBEDDED_FILES, tree);
+            if status != 0 {
+                anyhow::bail!("HPDF_NameDict_SetNameTree failed (status={})", status);
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Replace the Metadata stream of the catalog.
+    pub(crate) fn write_metadata(&self, xml: &str) -> anyhow::Result<()> {
+        let key = CString::new("Metadata")?;
+
+        let status = unsafe {
+            let catalog = ffi::doc_catalog(self.handle())?;
+            let mut stream = ffi::HPDF_Dict_GetItem(catalog, key.as_ptr(), ffi::HPDF_OCLASS_DICT);
+            if stream.is_null() {
+                stream = ffi::HPDF_DictStream_New(ffi::doc_mmgr(self.handle())?, ffi::doc_xref(self.handle())?);
+                if stream.is_null() {
+                    anyhow::bail!("HPDF_DictStream_New failed");
+                }
+
+                ffi::check(ffi::HPDF_Dict_AddName(stream, CString::new("Type")?.as_ptr(), CString::new("Metadata")?.as_ptr()), "HPDF_Dict_AddName")?;
+                ffi::check(ffi::HPDF_Dict_Add(catalog, key.as_ptr(), stream), "HPDF_Dict_Add")?;
+            }
+
+            /* HPDF_PDFA_SetPDFAConformance misspells the key as "SubType" */
+            ffi::HPDF_Dict_RemoveElement(stream, CString::new("SubType")?.as_ptr());
+            let status = ffi::HPDF_Dict_AddName(stream, CString::new("Subtype")?.as_ptr(), CString::new("XML")?.as_ptr());
+            if status != 0 {
+                anyhow::bail!("HPDF_Dict_AddName failed (status={})", status);
+            }
+
+            let data = ffi::dict_stream(stream)?;
+            ffi::HPDF_MemStream_FreeData(data);
+            ffi::HPDF_Stream_Write(data, xml.as_ptr(), xml.len().try_into()?)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Stream_Write failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Add the sRGB output intent required by PDF/A.
+    fn add_srgb_output_intent(&self) -> anyhow::Result<()> {
+        let profile = self.load_srgb_icc_profile()?;
+
+        let status = unsafe {
+            /* second element of [/ICCBased stream] */
+            let icc = ffi::HPDF_Array_GetItem(profile.handle(), 1, ffi::HPDF_OCLASS_DICT);
+            ffi::HPDF_PDFA_AppendOutputIntents(self.handle(), CString::new("sRGB IEC61966-2.1")?.as_ptr(), icc)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_PDFA_AppendOutputIntents failed (status={})", status);
+        }
+
+        /* libharu adds the profile with the key "DestOutputProfile " */
+        let status = unsafe {
+            let intents = ffi::HPDF_Dict_GetItem(ffi::doc_catalog(self.handle())?, CString::new("OutputIntents")?.as_ptr(), ffi::HPDF_OCLASS_ARRAY);
+            let intent = ffi::HPDF_Array_GetItem(intents, 0, ffi::HPDF_OCLASS_DICT);
+            let key = CString::new("DestOutputProfile ")?;
+            let icc = ffi::HPDF_Dict_GetItem(intent, key.as_ptr(), ffi::HPDF_OCLASS_DICT);
+            if icc.is_null() {
+                anyhow::bail!("HPDF_PDFA_AppendOutputIntents added no profile");
+            }
+
+            let status = ffi::HPDF_Dict_Add(intent, CString::new("DestOutputProfile")?.as_ptr(), icc);
+            if status != 0 {
+                status
+            } else {
+                ffi::HPDF_Dict_RemoveElement(intent, key.as_ptr())
+            }
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Write the XMP metadata and bring the document into the state required by its conformance level before saving.
+    fn prepare_save(&self) -> anyhow::Result<()> {
+        let conformance = self.conformance();
+        let xmp = self.inner.xmp.borrow();
+        if conformance == Conformance::None && xmp.is_none() {
+            return Ok(());
+        }
+
+        let packet = match xmp.as_ref() {
+            Some(xmp) => xmp.to_packet(&self.inner.info.borrow(), conformance)?,
+            None => XmpMetadata::new().to_packet(&self.inner.info.borrow(), conformance)?,
+        };
+
+        let catalog = unsafe { ffi::doc_catalog(self.handle())? };
+        let key = CString::new("Metadata")?;
+
+        if conformance != Conformance::None {
+            /* libharu writes the metadata with the pdfaid of the conformance level, and the file
+             * identifier if the info dictionary has an entry. The packet is written again below,
+             * with the escaped values and the custom XMP properties. */
+            let has_metadata = unsafe {
+                !ffi::HPDF_Dict_GetItem(catalog, key.as_ptr(), ffi::HPDF_OCLASS_DICT).is_null()
+            };
+            if !has_metadata {
+                let version = unsafe { ffi::pdf_version(self.handle())? };
+                let status = unsafe {
+                    ffi::HPDF_PDFA_SetPDFAConformance(self.handle(), libharu_sys::HPDF_PDFA_TYPE::HPDF_PDFA_1B)
+                };
+
+                if status != 0 {
+                    anyhow::bail!("HPDF_PDFA_SetPDFAConformance failed (status={})", status);
+                }
+
+                /* the version is set to 1.4 even if it was higher */
+                unsafe { ffi::require_version(self.handle(), version)? };
+            }
+
+            let has_intents = unsafe {
+                !ffi::HPDF_Dict_GetItem(catalog, CString::new("OutputIntents")?.as_ptr(), ffi::HPDF_OCLASS_ARRAY).is_null()
+            };
+            if !has_intents {
+                self.add_srgb_output_intent()?;
+            }
+
+            let status = unsafe {
+                ffi::HPDF_PDFA_GenerateID(self.handle())
+            };
+
+            if status != 0 {
+                anyhow::bail!("HPDF_PDFA_GenerateID failed (status={})", status);
+            }
+        }
+
+        /* Metadata streams and PDF/A-1 require PDF 1.4 */
+        unsafe { ffi::require_version(self.handle(), ffi::HPDF_VER_14)? };
+
+        self.write_metadata(&packet)
+    }
 }
 
 impl Drop for Document {
@@ -671,9 +1996,63 @@ extern "C" fn onerror_callback(
     detailno: libharu_sys::HPDF_STATUS,
     userdata: libharu_sys::HPDF_HANDLE)
 {
-    let inner: &mut DocumentInner = unsafe { std::mem::transmute(userdata) };
-    inner.last_errno = errno;
-    inner.last_detailno = detailno;
+    let inner: &DocumentInner = unsafe { &*(userdata as *const DocumentInner) };
+    let error = Error::from_num(errno);
 
-    (inner.onerror)(Error::from_num(errno));
+    /* keep the first unrecoverable error, it may be replaced by InvalidDocument later */
+    if !error.is_recoverable() && inner.fatal_error.get().is_none() {
+        inner.fatal_error.set(Some((errno, detailno)));
+    }
+
+    (inner.onerror)(ErrorDetail { error, detail_no: detailno });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn pages_are_visited_in_document_order() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let second = doc.add_page().unwrap();
+        let first = doc.insert_page(&second).unwrap();
+
+        let pages = doc.pages();
+        assert_eq!(pages.len(), 2);
+        let handles: Vec<_> = pages.map(|page| page.unwrap().handle()).collect();
+        assert_eq!(handles, [first.handle(), second.handle()]);
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-internals")]
+    fn form_xobject_leaves_no_orphan_objects() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let font = doc.font("Helvetica", None).unwrap();
+
+        let form = doc.create_form_xobject(100.0, 40.0, |page| {
+            page.set_font_and_size(&font, 12.0)?;
+            page.run_text_mode(|page| page.text_out((10.0, 10.0), "form"))?;
+            page.run_path_mode(|page| {
+                page.rectangle((0.0, 0.0), 100.0, 40.0)?;
+                page.stroke()
+            })
+        }).unwrap();
+
+        let page = doc.add_page().unwrap();
+        PageDescriptionMode::new(&page).execute_xobject(&form).unwrap();
+
+        let pdf = String::from_utf8_lossy(&doc.save_to_bytes().unwrap()).into_owned();
+        assert!(pdf.contains("/Subtype /Form"));
+        assert!(pdf.contains("/Matrix [ 1 0 0 1 0 0 ]"));
+
+        /* every object but the first is referenced from another object or the trailer */
+        let ids: Vec<&str> = pdf.lines()
+            .filter_map(|line| line.strip_suffix(" 0 obj"))
+            .collect();
+        assert!(!ids.is_empty());
+        for id in ids {
+            assert!(pdf.contains(&format!("{} 0 R", id)), "object {} is not referenced", id);
+        }
+    }
 }
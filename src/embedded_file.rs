@@ -0,0 +1,75 @@
+use crate::document::Document;
+use crate::ffi;
+
+use std::ffi::CString;
+
+/// Embedded file handle type.
+pub struct EmbeddedFile<'a> {
+    doc: &'a Document,
+    efile: libharu_sys::HPDF_EmbeddedFile,
+}
+
+impl<'a> EmbeddedFile<'a> {
+    pub(crate) fn new(doc: &'a Document, efile: libharu_sys::HPDF_EmbeddedFile) -> Self {
+        Self { doc, efile }
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> libharu_sys::HPDF_EmbeddedFile {
+        self.doc.enter_memory();
+        self.efile
+    }
+
+    /// Set the description of the embedded file.
+    pub fn set_description(&self, description: &str) -> anyhow::Result<()> {
+        let key = CString::new("Desc")?;
+
+        let status = unsafe {
+            let desc = ffi::text_string_new(ffi::dict_mmgr(self.handle()), description)?;
+            ffi::HPDF_Dict_Add(self.handle(), key.as_ptr(), desc)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Get the stream object which holds the contents of the file.
+    pub(crate) fn file_stream(&self) -> anyhow::Result<libharu_sys::HPDF_Dict> {
+        let ef_key = CString::new("EF")?;
+        let f_key = CString::new("F")?;
+
+        unsafe {
+            let ef = ffi::HPDF_Dict_GetItem(self.handle(), ef_key.as_ptr(), ffi::HPDF_OCLASS_DICT);
+            if ef.is_null() {
+                anyhow::bail!("HPDF_Dict_GetItem failed");
+            }
+
+            let stream = ffi::HPDF_Dict_GetItem(ef, f_key.as_ptr(), ffi::HPDF_OCLASS_DICT);
+            if stream.is_null() {
+                anyhow::bail!("HPDF_Dict_GetItem failed");
+            }
+
+            Ok(stream)
+        }
+    }
+
+    /// Set the MIME type (e.g. "text/csv") of the embedded file.
+    pub fn set_mime_type(&self, mime_type: &str) -> anyhow::Result<()> {
+        let key = CString::new("Subtype")?;
+        let mime_type = CString::new(mime_type)?;
+        let stream = self.file_stream()?;
+
+        let status = unsafe {
+            ffi::HPDF_Dict_AddName(stream, key.as_ptr(), mime_type.as_ptr())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_AddName failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
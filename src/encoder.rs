@@ -1,34 +1,65 @@
 use crate::document::Document;
 
-use std::ffi::CString;
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+use std::ops::Range;
 
 /// encoder type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EncoderType {
+    /// Single byte encoder.
     SingleByte,
+
+    /// Multi byte encoder.
     DoubleByte,
+
+    /// The encoder is not initialized yet.
     Uninitialized,
+
+    /// Invalid encoder.
     Unknown,
 }
 
 /// byte type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ByteType {
+    /// Single byte character.
     Single,
+
+    /// Lead byte of double byte character.
     Lead,
+
+    /// Trailing byte of double byte character.
     Trial,
+
+    /// Invalid encoder or position.
     Unknown,
 }
 
+/// Writing mode of an encoder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WritingMode {
+    /// Texts are written from left to right.
+    Horizontal,
+
+    /// Texts are written from top to bottom.
+    Vertical,
+}
+
+/// Encoder handle type.
 pub struct Encoder<'a> {
     enc: libharu_sys::HPDF_Encoder,
-    _doc: &'a Document,
+    name: String,
+    doc: &'a Document,
 }
 
 impl<'a> Encoder<'a> {
-    pub(crate) fn new(_doc: &'a Document, enc: libharu_sys::HPDF_Encoder) -> Self {
-        Self { enc, _doc }
+    pub(crate) fn new(doc: &'a Document, enc: libharu_sys::HPDF_Encoder, name: &str) -> Self {
+        Self { enc, name: name.to_string(), doc }
     }
 
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Encoder {
+        self.doc.enter_memory();
         self.enc
     }
 
@@ -46,18 +77,319 @@ impl<'a> Encoder<'a> {
         })
     }
 
+    /// Get the name of the encoder.
+    pub fn name(&self) -> anyhow::Result<&str> {
+        Ok(&self.name)
+    }
+
+    /// Get the writing mode of the encoder.
+    pub fn writing_mode(&self) -> anyhow::Result<WritingMode> {
+        let mode = unsafe {
+            libharu_sys::HPDF_Encoder_GetWritingMode(self.handle())
+        };
+
+        Ok(match mode {
+            libharu_sys::HPDF_WritingMode::HPDF_WMODE_VERTICAL => WritingMode::Vertical,
+            _ => WritingMode::Horizontal,
+        })
+    }
+
+    /// Get the unicode character of the character code.
+    ///
+    /// A double byte code is specified as `(lead << 8) | trail`, and the code of the UTF-8 encoder
+    /// is the unicode value itself. Returns `None` if the code is not mapped. libharu maps the
+    /// undefined codes of the CJK encoders to U+25A1.
+    pub fn unicode(&self, code: u16) -> anyhow::Result<Option<char>> {
+        if self.is_utf8() {
+            return Ok(std::char::from_u32(code as u32));
+        }
+
+        let unicode = unsafe {
+            libharu_sys::HPDF_Encoder_GetUnicode(self.handle(), code)
+        };
+
+        if unicode == 0 && code != 0 {
+            return Ok(None);
+        }
+
+        Ok(std::char::from_u32(unicode as u32))
+    }
+
+    /// Create an iterator which decodes the encoded text into `(byte range, char)` pairs.
+    ///
+    /// The bytes which cannot be decoded are yielded as U+FFFD.
+    pub fn decode(&self, text: &[u8]) -> anyhow::Result<DecodeIter<'_>> {
+        Ok(DecodeIter {
+            encoder: self,
+            utf8: self.is_utf8(),
+            text: CString::new(text)?,
+            pos: 0,
+        })
+    }
+
     /// Get the type of byte in the text at position index.
     pub fn byte_type(&self, text: &str, index: usize) -> anyhow::Result<ByteType> {
         let text = CString::new(text)?;
+
+        Ok(self.raw_byte_type(&text, index))
+    }
+
+    fn is_utf8(&self) -> bool {
+        matches!(self.name(), Ok("UTF-8"))
+    }
+
+    fn raw_byte_type(&self, text: &CStr, index: usize) -> ByteType {
+        /* HPDF_Encoder_GetByteType crashes with the UTF-8 encoder */
+        if self.is_utf8() {
+            return utf8_byte_type(text.to_bytes(), index);
+        }
+
         let byte_type = unsafe {
             libharu_sys::HPDF_Encoder_GetByteType(self.handle(), text.as_ptr(), index as libharu_sys::HPDF_UINT)
         };
 
-        Ok(match byte_type {
+        match byte_type {
             libharu_sys::HPDF_ByteType::HPDF_BYTE_TYPE_SINGLE => ByteType::Single,
             libharu_sys::HPDF_ByteType::HPDF_BYTE_TYPE_LEAD => ByteType::Lead,
             libharu_sys::HPDF_ByteType::HPDF_BYTE_TYPE_TRIAL => ByteType::Trial,
             libharu_sys::HPDF_ByteType::HPDF_BYTE_TYPE_UNKNOWN => ByteType::Unknown,
-        })
+        }
+    }
+}
+
+/// Get the type of byte in the UTF-8 text, in the same manner as the UTF-8 encoder of libharu.
+///
+/// The last byte of a character is `Single`, and the preceding bytes are `Trial`.
+fn utf8_byte_type(text: &[u8], index: usize) -> ByteType {
+    let mut remain = 0;
+    for (i, b) in text.iter().enumerate() {
+        let byte_type = if remain > 0 {
+            remain -= 1;
+            if remain == 0 { ByteType::Single } else { ByteType::Trial }
+        }
+        else {
+            match b {
+                0x00..=0x7f => ByteType::Single,
+                0xc0..=0xdf => { remain = 1; ByteType::Trial },
+                0xe0..=0xef => { remain = 2; ByteType::Trial },
+                0xf0..=0xf7 => { remain = 3; ByteType::Trial },
+                _ => ByteType::Trial,
+            }
+        };
+
+        if i == index {
+            return byte_type;
+        }
+    }
+
+    ByteType::Unknown
+}
+
+/// Iterator over the characters of an encoded text, created by `Encoder::decode`.
+pub struct DecodeIter<'a> {
+    encoder: &'a Encoder<'a>,
+    utf8: bool,
+    text: CString,
+    pos: usize,
+}
+
+impl<'a> Iterator for DecodeIter<'a> {
+    type Item = (Range<usize>, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+        let start = self.pos;
+        if start >= bytes.len() {
+            return None;
+        }
+
+        if self.utf8 {
+            let rest = &bytes[start..bytes.len().min(start + 4)];
+            let valid = match std::str::from_utf8(rest) {
+                Ok(s) => s,
+                Err(e) => std::str::from_utf8(&rest[..e.valid_up_to()]).unwrap_or_default(),
+            };
+
+            let (len, ch) = match valid.chars().next() {
+                Some(ch) => (ch.len_utf8(), ch),
+                None => (std::str::from_utf8(rest).err().and_then(|e| e.error_len()).unwrap_or(rest.len()), '\u{FFFD}'),
+            };
+
+            self.pos = start + len;
+            return Some((start..self.pos, ch));
+        }
+
+        /* parse from the character boundary */
+        let head = CStr::from_bytes_with_nul(&self.text.as_bytes_with_nul()[start..]).ok()?;
+        let mut end = start;
+        let code = loop {
+            let byte_type = self.encoder.raw_byte_type(head, end - start);
+            end += 1;
+
+            match byte_type {
+                ByteType::Single => break Some(bytes[end - 1] as u16),
+                ByteType::Lead if end < bytes.len() => {
+                    end += 1;
+                    break Some(((bytes[end - 2] as u16) << 8) | bytes[end - 1] as u16);
+                }
+                ByteType::Trial if end < bytes.len() => continue,
+                _ => break None,
+            }
+        };
+
+        self.pos = end;
+
+        let ch = code
+            .and_then(|code| self.encoder.unicode(code).ok().flatten())
+            .unwrap_or('\u{FFFD}');
+
+        Some((start..end, ch))
+    }
+}
+
+/// Error returned when a text cannot be represented in the encoding of the current font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodingError {
+    /// The first character which cannot be encoded.
+    pub character: char,
+
+    /// Name of the encoding.
+    pub encoding: String,
+}
+
+impl std::fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "character '{}' (U+{:04X}) cannot be encoded in \"{}\"", self.character, self.character as u32, self.encoding)
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// Get the encoding which texts for the multi-byte encoder are converted into.
+///
+/// The second value tells the encoder accepts only the EUC code set (0xA1 ~ 0xFE).
+#[cfg(feature = "encoding")]
+fn legacy_encoding(encoder: &str) -> Option<(&'static encoding_rs::Encoding, bool)> {
+    Some(match encoder {
+        "90ms-RKSJ-H" | "90ms-RKSJ-V" | "90msp-RKSJ-H" => (encoding_rs::SHIFT_JIS, false),
+        "EUC-H" | "EUC-V" => (encoding_rs::EUC_JP, false),
+        "GB-EUC-H" | "GB-EUC-V" => (encoding_rs::GBK, true),
+        "GBK-EUC-H" | "GBK-EUC-V" => (encoding_rs::GBK, false),
+        "KSC-EUC-H" | "KSC-EUC-V" => (encoding_rs::EUC_KR, true),
+        "KSCms-UHC-H" | "KSCms-UHC-HW-H" | "KSCms-UHC-HW-V" => (encoding_rs::EUC_KR, false),
+        "ETen-B5-H" | "ETen-B5-V" => (encoding_rs::BIG5, false),
+        _ => return None,
+    })
+}
+
+/// Convert the text into the encoding of the encoder.
+///
+/// Texts for the encoders other than the CJK multi-byte encoders are passed as UTF-8.
+#[cfg(feature = "encoding")]
+pub(crate) fn encode_str<'t>(encoder: &str, text: &'t str) -> Result<Cow<'t, [u8]>, EncodingError> {
+    let (encoding, euc_only) = match legacy_encoding(encoder) {
+        Some(v) => v,
+        None => return Ok(Cow::Borrowed(text.as_bytes())),
+    };
+
+    if text.is_ascii() {
+        return Ok(Cow::Borrowed(text.as_bytes()));
+    }
+
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut buf = [0; 4];
+    for c in text.chars() {
+        if c.is_ascii() {
+            bytes.push(c as u8);
+            continue;
+        }
+
+        let (encoded, _, unmappable) = encoding.encode(c.encode_utf8(&mut buf));
+        if unmappable || (euc_only && !encoded.iter().all(|b| (0xa1..=0xfe).contains(b))) {
+            return Err(EncodingError { character: c, encoding: encoder.to_string() });
+        }
+
+        bytes.extend_from_slice(&encoded);
+    }
+
+    Ok(Cow::Owned(bytes))
+}
+
+/// Convert the text into the encoding of the encoder.
+///
+/// Texts are passed as UTF-8 without the `encoding` feature.
+#[cfg(not(feature = "encoding"))]
+pub(crate) fn encode_str<'t>(_encoder: &str, text: &'t str) -> Result<Cow<'t, [u8]>, EncodingError> {
+    Ok(Cow::Borrowed(text.as_bytes()))
+}
+
+/// Get the byte length of the head of the text, which is converted into `len` bytes or less.
+pub(crate) fn source_len(encoder: &str, text: &str, len: usize) -> usize {
+    let mut encoded = 0;
+    let mut buf = [0; 4];
+    for (i, c) in text.char_indices() {
+        encoded += encode_str(encoder, c.encode_utf8(&mut buf)).map(|b| b.len()).unwrap_or(0);
+        if encoded > len {
+            return i;
+        }
+    }
+
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(encoder: &Encoder, text: &[u8]) -> Vec<(Range<usize>, char)> {
+        encoder.decode(text).unwrap().collect()
+    }
+
+    #[test]
+    fn utf8_byte_types() {
+        let text = "aé日😀".as_bytes();
+        let types: Vec<ByteType> = (0..text.len()).map(|i| utf8_byte_type(text, i)).collect();
+
+        use ByteType::{Single as S, Trial as T};
+        assert_eq!(types, [S, T, S, T, T, S, T, T, T, S]);
+        assert_eq!(utf8_byte_type(text, text.len()), ByteType::Unknown);
+
+        /* a stray continuation byte does not start a character */
+        assert_eq!(utf8_byte_type(b"\x80a", 0), ByteType::Trial);
+        assert_eq!(utf8_byte_type(b"\x80a", 1), ByteType::Single);
+    }
+
+    #[test]
+    fn decode_invalid_utf8() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        doc.use_utfencodings().unwrap();
+        let encoder = doc.find_encoder("UTF-8").unwrap();
+
+        assert_eq!(decoded(&encoder, "a日".as_bytes()), [(0..1, 'a'), (1..4, '日')]);
+
+        /* an invalid byte, and a truncated sequence followed by another character */
+        assert_eq!(decoded(&encoder, b"a\xff\xe6\x97b"), [(0..1, 'a'), (1..2, '\u{FFFD}'), (2..4, '\u{FFFD}'), (4..5, 'b')]);
+
+        /* a truncated sequence at the end */
+        assert_eq!(decoded(&encoder, b"b\xe6\x97"), [(0..1, 'b'), (1..3, '\u{FFFD}')]);
+    }
+
+    #[test]
+    fn decode_shift_jis() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        doc.use_jpencodings().unwrap();
+        let encoder = doc.find_encoder("90ms-RKSJ-H").unwrap();
+
+        /* "aあｱ" : ASCII, a double byte character and a half-width katakana */
+        let text = b"a\x82\xa0\xb1";
+        let types: Vec<ByteType> = (0..text.len())
+            .map(|i| encoder.raw_byte_type(&CString::new(&text[..]).unwrap(), i))
+            .collect();
+        assert_eq!(types, [ByteType::Single, ByteType::Lead, ByteType::Trial, ByteType::Single]);
+
+        assert_eq!(decoded(&encoder, text), [(0..1, 'a'), (1..3, 'あ'), (3..4, 'ｱ')]);
+
+        /* a lead byte without the trail byte */
+        assert_eq!(decoded(&encoder, b"a\x82"), [(0..1, 'a'), (1..2, '\u{FFFD}')]);
     }
 }
@@ -1,117 +1,156 @@
 
-#[derive(Debug)]
-pub enum Error {
-    ArrayCountError,
-    ArrayItemNotFound,
-    ArrayItemUnexpectedType,
-    BinaryLengthError,
-    CannotGetPallet,
-    DictCountError,
-    DictItemNotFound,
-    DictItemUnexpectedType,
-    DictStreamLengthNotFound,
-    DocEncryptdictNotFound,
-    DocInvalidObject,
-    DuplicateRegistration,
-    ExceedJwwCodeNumLimit,
-    EncryptInvalidPassword,
-    ErrorUnknownClass,
-    ExceedGstateLimit,
-    FailedToAllocMem,
-    FileIoError,
-    FileOpenError,
-    FontExists,
-    FontInvalidWidthsTable,
-    InvalidAfmHeader,
-    InvalidAnnotation,
-    InvalidBitPerComponent,
-    InvalidCharMatricsData,
-    InvalidColorSpace,
-    InvalidCompressionMode,
-    InvalidDateTime,
-    InvalidDestination,
-    InvalidDocument,
-    InvalidDocumentState,
-    InvalidEncoder,
-    InvalidEncoderType,
-    InvalidEncodingName,
-    InvalidEncryptKeyLen,
-    InvalidFontdefData,
-    InvalidFOntDefType,
-    InvalidFontName,
-    InvalidImage,
-    InvalidJpegData,
-    InvalidNData,
-    InvalidObject,
-    InvalidObjectId,
-    InvalidOperation,
-    InvalidOutline,
-    InvalidPage,
-    InvalidPages,
-    InvalidParameter,
-    InvalidPngImage,
-    InvalidStream,
-    MissingFileNameEntry,
-    InvalidTtcFile,
-    InvalidTtcIndex,
-    InvalidWxData,
-    ItemNotFound,
-    LibpngError,
-    NameInvalidValue,
-    NameOutOfRange,
-    PageInvalidParamCount,
-    PagesMissingKidsEntry,
-    PageCannotFindObject,
-    PageCannotGetRootPages,
-    PageCannotRestoreGstate,
-    PageCannotSetParent,
-    PageFontNotFound,
-    PageInvalidFont,
-    PageInvalidFontSize,
-    PageInvalidGMode,
-    PageInvalidIndex,
-    PageInvalidRotateValue,
-    PageInvalidSize,
-    PageInvalidXobject,
-    PageOutOfRange,
-    RealOutOfRange,
-    StreamEof,
-    StreamReadlnContinue,
-    StringOutOfRange,
-    ThisFuncWasSkipped,
-    TtfCannotEmbeddingFont,
-    TtfInvalidCmap,
-    TtfInvalidFormat,
-    TtfMissingTable,
-    UnsupportedFontType,
-    UnsupportedFunc,
-    UnsupportedJpegFormat,
-    UnsupportedType1Font,
-    XrefCountError,
-    ZlibError,
-    InvalidPageIndex,
-    InvalidUri,
-    PageLayoutOutOfRange,
-    PageModeOutOfRange,
-    PageNumStyleOutOfRange,
-    AnnotInvalidIcon,
-    AnnotInvalidBorderStyle,
-    PageInvalidDirection,
-    InvalidFont,
-    PageInsufficientSpace,
-    PageInvalidDisplayTime,
-    PageInvalidTransitionTime,
-    InvalidPageSlideshowType,
-    ExtGstateOutOfRange,
-    InvalidExtGstate,
-    ExtGstateReadOnly,
-    InvalidU3dData,
-    NameCannotGetNames,
-    InvalidIccComponentNum,
-    Other(libharu_sys::HPDF_STATUS)
+/// Declare the error codes with their descriptions, which are both the doc comments of the
+/// variants and the messages returned by `Error::message`.
+macro_rules! error_codes {
+    ($($name:ident => $message:literal,)*) => {
+        /// Error codes of libharu.
+        #[derive(Debug)]
+        pub enum Error {
+            $(
+                #[doc = $message]
+                $name,
+            )*
+            /// Unknown error code.
+            Other(libharu_sys::HPDF_STATUS)
+        }
+
+        impl Error {
+            /// Get the human-readable description of the error.
+            pub fn message(&self) -> &'static str {
+                match self {
+                    $(Self::$name => $message,)*
+                    Self::Other(_) => "Unknown error code.",
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    ArrayCountError => "Internal error. Data consistency was lost.",
+    ArrayItemNotFound => "Internal error. Data consistency was lost.",
+    ArrayItemUnexpectedType => "Internal error. Data consistency was lost.",
+    BinaryLengthError => "Data length > HPDF_LIMIT_MAX_STRING_LEN.",
+    CannotGetPallet => "Cannot get pallet data from PNG image.",
+    DictCountError => "Dictionary elements > HPDF_LIMIT_MAX_DICT_ELEMENT.",
+    DictItemNotFound => "Internal error. Data consistency was lost.",
+    DictItemUnexpectedType => "Internal error. Data consistency was lost.",
+    DictStreamLengthNotFound => "Internal error. Data consistency was lost.",
+    DocEncryptdictNotFound => "Encryption mode was set, but the encryption dictionary was not found.",
+    DocInvalidObject => "Internal error. Data consistency was lost.",
+    DuplicateRegistration => "Tried to register a font that has been registered.",
+    ExceedJwwCodeNumLimit => "Cannot register a character to the Japanese word wrap characters list.",
+    EncryptInvalidPassword => "The owner password is empty, or the owner password and the user password are the same.",
+    ErrorUnknownClass => "Internal error. Data consistency was lost.",
+    ExceedGstateLimit => "The depth of the graphics state stack exceeded HPDF_LIMIT_MAX_GSTATE.",
+    FailedToAllocMem => "Memory allocation failed.",
+    FileIoError => "File processing failed.",
+    FileOpenError => "Cannot open a file.",
+    FontExists => "Tried to load a font that has been registered.",
+    FontInvalidWidthsTable => "The font file format is invalid, or internal error.",
+    InvalidAfmHeader => "Cannot recognize the header of an AFM file.",
+    InvalidAnnotation => "The specified annotation handle is invalid.",
+    InvalidBitPerComponent => "Bit-per-component of an image is invalid.",
+    InvalidCharMatricsData => "Cannot recognize the char-matrics-data of an AFM file.",
+    InvalidColorSpace => "The color space of an image is invalid.",
+    InvalidCompressionMode => "An invalid value was set when invoking `Document::set_compression_mode`.",
+    InvalidDateTime => "An invalid date-time value was set.",
+    InvalidDestination => "An invalid destination handle was set.",
+    InvalidDocument => "An invalid document handle was set.",
+    InvalidDocumentState => "The function which is invalid in the present state was invoked.",
+    InvalidEncoder => "An invalid encoder handle was set.",
+    InvalidEncoderType => "A combination of the font and the encoder is wrong.",
+    InvalidEncodingName => "An invalid encoding name was specified.",
+    InvalidEncryptKeyLen => "The length of the encryption key is invalid.",
+    InvalidFontdefData => "An invalid font handle was set, or an unsupported font format.",
+    InvalidFOntDefType => "Internal error. Data consistency was lost.",
+    InvalidFontName => "A font with the specified name is not found.",
+    InvalidImage => "Unsupported image format.",
+    InvalidJpegData => "Unsupported JPEG format.",
+    InvalidNData => "Cannot read a postscript-name from an AFM file.",
+    InvalidObject => "An invalid object was set, or internal error.",
+    InvalidObjectId => "Internal error. Data consistency was lost.",
+    InvalidOperation => "An image which is not a mask was specified as a mask, or an invalid operation was invoked.",
+    InvalidOutline => "An invalid outline handle was specified.",
+    InvalidPage => "An invalid page handle was specified.",
+    InvalidPages => "An invalid pages handle was specified. (internal error)",
+    InvalidParameter => "An invalid value is set.",
+    InvalidPngImage => "Invalid PNG image format.",
+    InvalidStream => "Internal error. Data consistency was lost.",
+    MissingFileNameEntry => "Internal error. The \"_FILE_NAME\" entry for delayed loading is missing.",
+    InvalidTtcFile => "Invalid TrueType collection file format.",
+    InvalidTtcIndex => "The index of a TrueType collection font exceeded the number of included fonts.",
+    InvalidWxData => "Cannot read a width-data from an AFM file.",
+    ItemNotFound => "Internal error. Data consistency was lost.",
+    LibpngError => "An error was returned from libpng while loading an image.",
+    NameInvalidValue => "Internal error. Data consistency was lost.",
+    NameOutOfRange => "The name is empty or exceeds the length limit (127 bytes).",
+    PageInvalidParamCount => "Internal error. Data consistency was lost.",
+    PagesMissingKidsEntry => "Internal error. Data consistency was lost.",
+    PageCannotFindObject => "Internal error. Data consistency was lost.",
+    PageCannotGetRootPages => "Internal error. Data consistency was lost.",
+    PageCannotRestoreGstate => "There are no graphics states to be restored.",
+    PageCannotSetParent => "Internal error. Data consistency was lost.",
+    PageFontNotFound => "The current font is not set.",
+    PageInvalidFont => "An invalid font handle was specified.",
+    PageInvalidFontSize => "An invalid font size was set.",
+    PageInvalidGMode => "The function was invoked in an invalid graphics mode.",
+    PageInvalidIndex => "Internal error. Data consistency was lost.",
+    PageInvalidRotateValue => "The specified rotation value is not a multiple of 90.",
+    PageInvalidSize => "An invalid page size was set.",
+    PageInvalidXobject => "An invalid image handle was set.",
+    PageOutOfRange => "The specified value is out of range.",
+    RealOutOfRange => "The specified value is out of range.",
+    StreamEof => "Unexpected EOF marker was detected.",
+    StreamReadlnContinue => "Internal error. Data consistency was lost.",
+    StringOutOfRange => "The length of the text is too long.",
+    ThisFuncWasSkipped => "The execution of a function was skipped because of other errors.",
+    TtfCannotEmbeddingFont => "This font cannot be embedded. (restricted by license)",
+    TtfInvalidCmap => "Unsupported TrueType format. (cannot find unicode cmap)",
+    TtfInvalidFormat => "Unsupported TrueType format.",
+    TtfMissingTable => "Unsupported TrueType format. (cannot find a necessary table)",
+    UnsupportedFontType => "Internal error. Data consistency was lost.",
+    UnsupportedFunc => "The library is not configured to use PNGLIB, or internal error.",
+    UnsupportedJpegFormat => "Unsupported JPEG format.",
+    UnsupportedType1Font => "Failed to parse a PFB file.",
+    XrefCountError => "Internal error. Data consistency was lost.",
+    ZlibError => "An error occurred while executing a function of zlib.",
+    InvalidPageIndex => "An invalid page index was specified.",
+    InvalidUri => "An invalid URI was set.",
+    PageLayoutOutOfRange => "An invalid page layout was set.",
+    PageModeOutOfRange => "An invalid page mode was set.",
+    PageNumStyleOutOfRange => "An invalid page number style was set.",
+    AnnotInvalidIcon => "An invalid icon was set.",
+    AnnotInvalidBorderStyle => "An invalid border style was set.",
+    PageInvalidDirection => "An invalid page direction was set.",
+    InvalidFont => "An invalid font handle was specified.",
+    PageInsufficientSpace => "There is not enough space to show the text.",
+    PageInvalidDisplayTime => "An invalid display time of a slide show was set.",
+    PageInvalidTransitionTime => "An invalid transition time of a slide show was set.",
+    InvalidPageSlideshowType => "An invalid transition style of a slide show was set.",
+    ExtGstateOutOfRange => "The value of the extended graphics state is out of range.",
+    InvalidExtGstate => "An invalid extended graphics state handle was specified.",
+    ExtGstateReadOnly => "The extended graphics state is read only.",
+    InvalidU3dData => "Invalid U3D data.",
+    NameCannotGetNames => "Cannot get the names dictionary.",
+    InvalidIccComponentNum => "An invalid number of ICC profile components was specified.",
 }
 
 impl Error {
+    /// Check whether the document stays consistent after the error, so that `Document::recover`
+    /// can clear it. Memory allocation failures and internal errors are not recoverable.
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(self,
+            Self::ArrayCountError | Self::ArrayItemNotFound | Self::ArrayItemUnexpectedType |
+            Self::DictItemNotFound | Self::DictItemUnexpectedType | Self::DictStreamLengthNotFound |
+            Self::DocInvalidObject | Self::ErrorUnknownClass | Self::FailedToAllocMem |
+            Self::InvalidFOntDefType | Self::InvalidObjectId | Self::InvalidPages | Self::InvalidStream |
+            Self::MissingFileNameEntry | Self::ItemNotFound | Self::NameInvalidValue |
+            Self::PageInvalidParamCount | Self::PagesMissingKidsEntry | Self::PageCannotFindObject |
+            Self::PageCannotGetRootPages | Self::PageCannotSetParent | Self::PageInvalidIndex |
+            Self::StreamReadlnContinue | Self::UnsupportedFontType | Self::XrefCountError | Self::Other(_))
+    }
+
     pub(crate) fn from_num(num: libharu_sys::HPDF_STATUS) -> Self {
         match num {
             0x1001 => Self::ArrayCountError,
@@ -242,3 +281,42 @@ impl Error {
         }
     }
 }
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Other(n) => write!(f, "libharu error (status=0x{:04X})", n),
+            e => write!(f, "libharu error ({:?}): {}", e, e.message()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Error reported by libharu, with the detail number of the failure.
+///
+/// The meaning of the detail number depends on the error, e.g. the error number of a file
+/// operation for `Error::FileIoError`.
+#[derive(Debug)]
+pub struct ErrorDetail {
+    /// Error code.
+    pub error: Error,
+
+    /// Detail number. (0 if there is no detail)
+    pub detail_no: libharu_sys::HPDF_STATUS,
+}
+
+impl ErrorDetail {
+    /// Get the human-readable description of the error.
+    pub fn message(&self) -> &'static str {
+        self.error.message()
+    }
+}
+
+impl std::fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (detail={})", self.error, self.detail_no)
+    }
+}
+
+impl std::error::Error for ErrorDetail {}
@@ -0,0 +1,429 @@
+//! libharu functions and structures which are not declared by libharu-sys.
+//!
+//! libharu has no functions to reach the catalog, the cross reference table and the streams of a
+//! document, which some functions of this crate need. With the `bundled-internals` feature, they
+//! are read from the private structures of the libharu bundled with libharu-sys; the layout differs
+//! between libharu releases, so the accessors fail unless the bundled version is linked. Without the
+//! feature, the accessors always fail.
+#![allow(non_snake_case, non_camel_case_types)]
+
+use libharu_sys::*;
+
+pub(crate) use internals::*;
+
+#[cfg(feature = "bundled-internals")]
+mod internals {
+    use libharu_sys::*;
+
+    /// Header of libharu objects. (HPDF_Obj_Header)
+    #[repr(C)]
+    struct ObjHeader {
+        obj_id: HPDF_UINT32,
+        gen_no: HPDF_UINT16,
+        obj_class: HPDF_UINT16,
+    }
+
+    /// Dictionary object. (HPDF_Dict_Rec)
+    #[repr(C)]
+    struct DictRec {
+        header: ObjHeader,
+        mmgr: HPDF_MMgr,
+        error: HPDF_Error,
+        list: HPDF_HANDLE,
+        before_write_fn: HPDF_HANDLE,
+        write_fn: HPDF_HANDLE,
+        after_write_fn: HPDF_HANDLE,
+        free_fn: HPDF_HANDLE,
+        stream: HPDF_Stream,
+        filter: HPDF_UINT,
+        filter_params: HPDF_Dict,
+        attr: HPDF_HANDLE,
+    }
+
+    /// Real number object. (HPDF_Real_Rec)
+    #[repr(C)]
+    struct RealRec {
+        header: ObjHeader,
+        error: HPDF_Error,
+        value: HPDF_REAL,
+    }
+
+    /// Error object. (HPDF_Error_Rec)
+    #[repr(C)]
+    struct ErrorRec {
+        error_no: std::os::raw::c_ulong,
+        detail_no: std::os::raw::c_ulong,
+        error_fn: HPDF_HANDLE,
+        user_data: HPDF_HANDLE,
+    }
+
+    /// Document object. (leading fields of HPDF_Doc_Rec)
+    #[repr(C)]
+    struct DocRec {
+        sig_bytes: HPDF_UINT32,
+        pdf_version: std::os::raw::c_int,
+        mmgr: HPDF_MMgr,
+        catalog: HPDF_Dict,
+        outlines: HPDF_Outline,
+        xref: HPDF_Xref,
+        root_pages: HPDF_Pages,
+        cur_pages: HPDF_Pages,
+        cur_page: HPDF_Page,
+        page_list: HPDF_HANDLE,
+        error: ErrorRec,
+        info: HPDF_Dict,
+        trailer: HPDF_Dict,
+        font_mgr: HPDF_HANDLE,
+        ttfont_tag: [HPDF_BYTE; 6],
+        fontdef_list: HPDF_HANDLE,
+        encoder_list: HPDF_HANDLE,
+        cur_encoder: HPDF_Encoder,
+        compression_mode: HPDF_BOOL,
+    }
+
+    /// Page attributes. (leading fields of HPDF_PageAttr_Rec)
+    #[repr(C)]
+    struct PageAttrRec {
+        parent: HPDF_Pages,
+        fonts: HPDF_Dict,
+        xobjects: HPDF_Dict,
+        ext_gstates: HPDF_Dict,
+        gstate: HPDF_HANDLE,
+        str_pos: HPDF_Point,
+        cur_pos: HPDF_Point,
+        text_pos: HPDF_Point,
+        text_matrix: HPDF_TransMatrix,
+        gmode: HPDF_UINT16,
+        contents: HPDF_Dict,
+        stream: HPDF_Stream,
+    }
+
+    /// Graphics state. (leading fields of HPDF_GState_Rec)
+    #[repr(C)]
+    struct GStateRec {
+        trans_matrix: HPDF_TransMatrix,
+        line_width: HPDF_REAL,
+        line_cap: HPDF_LineCap,
+        line_join: HPDF_LineJoin,
+        miter_limit: HPDF_REAL,
+        dash_mode: HPDF_DashMode,
+        flatness: HPDF_REAL,
+        char_space: HPDF_REAL,
+        word_space: HPDF_REAL,
+        h_scalling: HPDF_REAL,
+        text_leading: HPDF_REAL,
+        rendering_mode: HPDF_TextRenderingMode,
+        text_rise: HPDF_REAL,
+        cs_fill: HPDF_ColorSpace,
+        cs_stroke: HPDF_ColorSpace,
+    }
+
+    const HPDF_OSUBCLASS_XOBJECT: HPDF_UINT16 = 0x0500;
+
+    extern "C" {
+        fn HPDF_GetVersion() -> *const std::os::raw::c_char;
+    }
+
+    /// Version of the libharu bundled with libharu-sys, which the records above are taken from.
+    const BUNDLED_VERSION: &str = "2.4.0dev";
+
+    /// Check that the linked libharu is the bundled one, so that the records above can be read.
+    pub(crate) fn require_internals() -> anyhow::Result<()> {
+        let version = unsafe { std::ffi::CStr::from_ptr(HPDF_GetVersion()) };
+        if version.to_bytes() != BUNDLED_VERSION.as_bytes() {
+            anyhow::bail!("libharu {} is linked, but the bundled-internals feature requires {}; build libharu-sys with HARU_NO_PKG_CONFIG=1", version.to_string_lossy(), BUNDLED_VERSION);
+        }
+
+        Ok(())
+    }
+
+    unsafe fn doc<'a>(pdf: HPDF_Doc) -> anyhow::Result<&'a mut DocRec> {
+        require_internals()?;
+        Ok(&mut *(pdf as *mut DocRec))
+    }
+
+    unsafe fn dict<'a>(dict: HPDF_Dict) -> anyhow::Result<&'a mut DictRec> {
+        require_internals()?;
+        Ok(&mut *(dict as *mut DictRec))
+    }
+
+    unsafe fn page_attr<'a>(page: HPDF_Page) -> anyhow::Result<&'a mut PageAttrRec> {
+        Ok(&mut *(dict(page)?.attr as *mut PageAttrRec))
+    }
+
+    /// Get the catalog of a document.
+    pub(crate) unsafe fn doc_catalog(pdf: HPDF_Doc) -> anyhow::Result<HPDF_Dict> {
+        Ok(doc(pdf)?.catalog)
+    }
+
+    /// Get the info dictionary of a document, which libharu creates by the first info attribute.
+    pub(crate) unsafe fn doc_info(pdf: HPDF_Doc) -> anyhow::Result<HPDF_Dict> {
+        Ok(doc(pdf)?.info)
+    }
+
+    /// Get the memory manager of a document.
+    pub(crate) unsafe fn doc_mmgr(pdf: HPDF_Doc) -> anyhow::Result<HPDF_MMgr> {
+        Ok(doc(pdf)?.mmgr)
+    }
+
+    /// Get the cross reference table of a document, which indirect objects are added to.
+    pub(crate) unsafe fn doc_xref(pdf: HPDF_Doc) -> anyhow::Result<HPDF_Xref> {
+        Ok(doc(pdf)?.xref)
+    }
+
+    /// Get the compression mode of a document.
+    pub(crate) unsafe fn doc_compression_mode(pdf: HPDF_Doc) -> anyhow::Result<HPDF_BOOL> {
+        Ok(doc(pdf)?.compression_mode)
+    }
+
+    /// Get the PDF version written in the header.
+    pub(crate) unsafe fn pdf_version(pdf: HPDF_Doc) -> anyhow::Result<std::os::raw::c_int> {
+        Ok(doc(pdf)?.pdf_version)
+    }
+
+    /// Raise the PDF version written in the header, as libharu has no function to set it.
+    pub(crate) unsafe fn require_version(pdf: HPDF_Doc, version: std::os::raw::c_int) -> anyhow::Result<()> {
+        let rec = doc(pdf)?;
+        if rec.pdf_version < version {
+            rec.pdf_version = version;
+        }
+
+        Ok(())
+    }
+
+    /// Get the data stream of a stream dictionary.
+    pub(crate) unsafe fn dict_stream(stream: HPDF_Dict) -> anyhow::Result<HPDF_Stream> {
+        Ok(dict(stream)?.stream)
+    }
+
+    /// Set the filter applied to the data of a stream dictionary when it is written.
+    pub(crate) unsafe fn set_stream_filter(stream: HPDF_Dict, filter: HPDF_UINT) -> anyhow::Result<()> {
+        dict(stream)?.filter = filter;
+        Ok(())
+    }
+
+    /// Mark a stream dictionary as an XObject, so that `HPDF_Page_ExecuteXObject` accepts it.
+    pub(crate) unsafe fn set_xobject_class(stream: HPDF_Dict) -> anyhow::Result<()> {
+        dict(stream)?.header.obj_class |= HPDF_OSUBCLASS_XOBJECT;
+        Ok(())
+    }
+
+    /// Get the content stream of a page.
+    pub(crate) unsafe fn page_stream(page: HPDF_Page) -> anyhow::Result<HPDF_Stream> {
+        Ok(page_attr(page)?.stream)
+    }
+
+    /// Get the value of a real number object, as returned by `HPDF_Array_GetItem` with `HPDF_OCLASS_REAL`.
+    pub(crate) unsafe fn real_value(real: HPDF_HANDLE) -> anyhow::Result<HPDF_REAL> {
+        require_internals()?;
+        Ok((*(real as *const RealRec)).value)
+    }
+
+    /// Set the filling or stroking color space in the graphics state of a page.
+    pub(crate) unsafe fn set_color_space(page: HPDF_Page, color_space: HPDF_ColorSpace, stroke: bool) -> anyhow::Result<()> {
+        let gstate = &mut *(page_attr(page)?.gstate as *mut GStateRec);
+        if stroke {
+            gstate.cs_stroke = color_space;
+        } else {
+            gstate.cs_fill = color_space;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fallbacks of the accessors above, which fail without the `bundled-internals` feature.
+#[cfg(not(feature = "bundled-internals"))]
+mod internals {
+    use libharu_sys::*;
+
+    fn unavailable<T>() -> anyhow::Result<T> {
+        anyhow::bail!("this function requires the bundled-internals feature of libharu")
+    }
+
+    pub(crate) fn require_internals() -> anyhow::Result<()> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn doc_catalog(_pdf: HPDF_Doc) -> anyhow::Result<HPDF_Dict> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn doc_info(_pdf: HPDF_Doc) -> anyhow::Result<HPDF_Dict> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn doc_mmgr(_pdf: HPDF_Doc) -> anyhow::Result<HPDF_MMgr> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn doc_xref(_pdf: HPDF_Doc) -> anyhow::Result<HPDF_Xref> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn doc_compression_mode(_pdf: HPDF_Doc) -> anyhow::Result<HPDF_BOOL> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn pdf_version(_pdf: HPDF_Doc) -> anyhow::Result<std::os::raw::c_int> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn require_version(_pdf: HPDF_Doc, _version: std::os::raw::c_int) -> anyhow::Result<()> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn dict_stream(_stream: HPDF_Dict) -> anyhow::Result<HPDF_Stream> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn set_stream_filter(_stream: HPDF_Dict, _filter: HPDF_UINT) -> anyhow::Result<()> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn set_xobject_class(_stream: HPDF_Dict) -> anyhow::Result<()> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn page_stream(_page: HPDF_Page) -> anyhow::Result<HPDF_Stream> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn real_value(_real: HPDF_HANDLE) -> anyhow::Result<HPDF_REAL> {
+        unavailable()
+    }
+
+    pub(crate) unsafe fn set_color_space(_page: HPDF_Page, _color_space: HPDF_ColorSpace, _stroke: bool) -> anyhow::Result<()> {
+        unavailable()
+    }
+}
+
+/// Get the memory manager of a dictionary object (page, outline, annotation and so on).
+///
+/// All objects of a document share its memory manager, and HPDF_GetPageMMgr only reads the
+/// dictionary part of the page.
+pub(crate) unsafe fn dict_mmgr(dict: HPDF_Dict) -> HPDF_MMgr {
+    HPDF_GetPageMMgr(dict)
+}
+
+/// Turn the status of a libharu function into an error.
+pub(crate) fn check(status: HPDF_STATUS, func: &str) -> anyhow::Result<()> {
+    if status != 0 {
+        anyhow::bail!("{} failed (status={})", func, status);
+    }
+
+    Ok(())
+}
+
+pub(crate) type HPDF_Array = HPDF_HANDLE;
+
+pub(crate) const HPDF_OCLASS_REAL: HPDF_UINT16 = 0x0005;
+pub(crate) const HPDF_OCLASS_STRING: HPDF_UINT16 = 0x0007;
+pub(crate) const HPDF_OCLASS_ARRAY: HPDF_UINT16 = 0x0010;
+pub(crate) const HPDF_OCLASS_DICT: HPDF_UINT16 = 0x0011;
+pub(crate) const HPDF_GMODE_PAGE_DESCRIPTION: HPDF_UINT16 = 0x0001;
+pub(crate) const HPDF_NAME_EMBEDDED_FILES: std::os::raw::c_int = 0;
+pub(crate) const HPDF_VER_14: std::os::raw::c_int = 2;
+pub(crate) const HPDF_VER_16: std::os::raw::c_int = 4;
+pub(crate) const HPDF_VER_17: std::os::raw::c_int = 5;
+pub(crate) const HPDF_COMP_TEXT: HPDF_BOOL = 0x01;
+pub(crate) const HPDF_STREAM_FILTER_FLATE_DECODE: HPDF_UINT = 0x0400;
+
+extern "C" {
+    pub(crate) fn HPDF_NewEx(
+        user_error_fn: extern "C" fn(HPDF_STATUS, HPDF_STATUS, HPDF_HANDLE),
+        user_alloc_fn: Option<crate::memory::AllocFunc>,
+        user_free_fn: Option<crate::memory::FreeFunc>,
+        mem_pool_buf_size: HPDF_UINT,
+        user_data: HPDF_HANDLE) -> HPDF_Doc;
+    pub(crate) fn HPDF_GetError(pdf: HPDF_Doc) -> HPDF_STATUS;
+    pub(crate) fn HPDF_GetErrorDetail(pdf: HPDF_Doc) -> HPDF_STATUS;
+    pub(crate) fn HPDF_ResetError(pdf: HPDF_Doc);
+    pub(crate) fn HPDF_NewDoc(pdf: HPDF_Doc) -> HPDF_STATUS;
+    pub(crate) fn HPDF_FreeDocAll(pdf: HPDF_Doc);
+    pub(crate) fn HPDF_SaveToStream(pdf: HPDF_Doc) -> HPDF_STATUS;
+    pub(crate) fn HPDF_GetStreamSize(pdf: HPDF_Doc) -> HPDF_UINT32;
+    pub(crate) fn HPDF_ReadFromStream(pdf: HPDF_Doc, buf: *mut HPDF_BYTE, size: *mut HPDF_UINT32) -> HPDF_STATUS;
+    pub(crate) fn HPDF_ResetStream(pdf: HPDF_Doc) -> HPDF_STATUS;
+    pub(crate) fn HPDF_SetPagesConfiguration(pdf: HPDF_Doc, page_per_pages: HPDF_UINT) -> HPDF_STATUS;
+    pub(crate) fn HPDF_GetPageByIndex(pdf: HPDF_Doc, index: HPDF_UINT) -> HPDF_Page;
+    /// Declared with the raw value, which is out of HPDF_PageMode for UseOC and UseAttachments.
+    pub(crate) fn HPDF_GetPageMode(pdf: HPDF_Doc) -> std::os::raw::c_int;
+
+    pub(crate) fn HPDF_SetInfoAttr(pdf: HPDF_Doc, type_: HPDF_InfoType, value: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_SetInfoDateAttr(pdf: HPDF_Doc, type_: HPDF_InfoType, value: HPDF_Date) -> HPDF_STATUS;
+    pub(crate) fn HPDF_GetInfoAttr(pdf: HPDF_Doc, type_: HPDF_InfoType) -> *const std::os::raw::c_char;
+    pub(crate) fn HPDF_PDFA_GenerateID(pdf: HPDF_Doc) -> HPDF_STATUS;
+    pub(crate) fn HPDF_PDFA_SetPDFAConformance(pdf: HPDF_Doc, pdfatype: HPDF_PDFA_TYPE) -> HPDF_STATUS;
+    pub(crate) fn HPDF_PDFA_AppendOutputIntents(pdf: HPDF_Doc, iccname: *const std::os::raw::c_char, iccdict: HPDF_Dict) -> HPDF_STATUS;
+
+    pub(crate) fn HPDF_Page_New(mmgr: HPDF_MMgr, xref: HPDF_Xref) -> HPDF_Page;
+    pub(crate) fn HPDF_Page_ExecuteXObject(page: HPDF_Page, obj: HPDF_XObject) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Page_GetInheritableItem(page: HPDF_Page, key: *const std::os::raw::c_char, obj_class: HPDF_UINT16) -> HPDF_HANDLE;
+
+    pub(crate) fn HPDF_Xref_New(mmgr: HPDF_MMgr, offset: HPDF_UINT32) -> HPDF_Xref;
+    pub(crate) fn HPDF_Xref_Free(xref: HPDF_Xref);
+    pub(crate) fn HPDF_Xref_Add(xref: HPDF_Xref, obj: HPDF_HANDLE) -> HPDF_STATUS;
+
+    pub(crate) fn HPDF_Catalog_GetNames(catalog: HPDF_Dict) -> HPDF_Dict;
+    pub(crate) fn HPDF_Catalog_SetNames(catalog: HPDF_Dict, dict: HPDF_Dict) -> HPDF_STATUS;
+    pub(crate) fn HPDF_NameDict_New(mmgr: HPDF_MMgr, xref: HPDF_Xref) -> HPDF_Dict;
+    pub(crate) fn HPDF_NameDict_GetNameTree(namedict: HPDF_Dict, key: std::os::raw::c_int) -> HPDF_Dict;
+    pub(crate) fn HPDF_NameDict_SetNameTree(namedict: HPDF_Dict, key: std::os::raw::c_int, tree: HPDF_Dict) -> HPDF_STATUS;
+    pub(crate) fn HPDF_NameTree_New(mmgr: HPDF_MMgr, xref: HPDF_Xref) -> HPDF_Dict;
+    pub(crate) fn HPDF_NameTree_Add(tree: HPDF_Dict, name: HPDF_HANDLE, obj: HPDF_HANDLE) -> HPDF_STATUS;
+
+    pub(crate) fn HPDF_MemStream_FreeData(stream: HPDF_Stream);
+    pub(crate) fn HPDF_Stream_Write(stream: HPDF_Stream, ptr: *const HPDF_BYTE, size: HPDF_UINT) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Stream_WriteStr(stream: HPDF_Stream, value: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Stream_WriteToStream(src: HPDF_Stream, dst: HPDF_Stream, filter: HPDF_UINT, e: HPDF_HANDLE) -> HPDF_STATUS;
+
+    pub(crate) fn HPDF_String_New(mmgr: HPDF_MMgr, value: *const std::os::raw::c_char, encoder: HPDF_Encoder) -> HPDF_HANDLE;
+    pub(crate) fn HPDF_Binary_New(mmgr: HPDF_MMgr, value: *const HPDF_BYTE, len: HPDF_UINT) -> HPDF_HANDLE;
+    pub(crate) fn HPDF_DictStream_New(mmgr: HPDF_MMgr, xref: HPDF_Xref) -> HPDF_Dict;
+
+    pub(crate) fn HPDF_Array_New(mmgr: HPDF_MMgr) -> HPDF_Array;
+    pub(crate) fn HPDF_Array_Add(array: HPDF_Array, obj: HPDF_HANDLE) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Array_AddReal(array: HPDF_Array, value: HPDF_REAL) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Array_GetItem(array: HPDF_Array, index: HPDF_UINT, obj_class: HPDF_UINT16) -> HPDF_HANDLE;
+    pub(crate) fn HPDF_Array_AddName(array: HPDF_Array, value: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Array_Insert(array: HPDF_Array, target: HPDF_HANDLE, obj: HPDF_HANDLE) -> HPDF_STATUS;
+
+    pub(crate) fn HPDF_Dict_New(mmgr: HPDF_MMgr) -> HPDF_Dict;
+    pub(crate) fn HPDF_Dict_GetKeyByObj(dict: HPDF_Dict, obj: HPDF_HANDLE) -> *const std::os::raw::c_char;
+    pub(crate) fn HPDF_Dict_GetItem(dict: HPDF_Dict, key: *const std::os::raw::c_char, obj_class: HPDF_UINT16) -> HPDF_HANDLE;
+    pub(crate) fn HPDF_Dict_RemoveElement(dict: HPDF_Dict, key: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Dict_AddName(dict: HPDF_Dict, key: *const std::os::raw::c_char, value: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Dict_Add(dict: HPDF_Dict, key: *const std::os::raw::c_char, obj: HPDF_HANDLE) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Dict_AddNumber(dict: HPDF_Dict, key: *const std::os::raw::c_char, value: HPDF_INT32) -> HPDF_STATUS;
+
+    pub(crate) fn HPDF_U3D_Add3DView(u3d: HPDF_U3D, view: HPDF_Dict) -> HPDF_STATUS;
+    pub(crate) fn HPDF_U3D_SetDefault3DView(u3d: HPDF_U3D, name: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_Create3DView(mmgr: HPDF_MMgr, name: *const std::os::raw::c_char) -> HPDF_Dict;
+    pub(crate) fn HPDF_3DView_SetLighting(view: HPDF_Dict, scheme: *const std::os::raw::c_char) -> HPDF_STATUS;
+    pub(crate) fn HPDF_3DView_SetBackgroundColor(view: HPDF_Dict, r: HPDF_REAL, g: HPDF_REAL, b: HPDF_REAL) -> HPDF_STATUS;
+    pub(crate) fn HPDF_3DView_SetPerspectiveProjection(view: HPDF_Dict, fov: HPDF_REAL) -> HPDF_STATUS;
+    pub(crate) fn HPDF_3DView_SetOrthogonalProjection(view: HPDF_Dict, mag: HPDF_REAL) -> HPDF_STATUS;
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn HPDF_3DView_SetCamera(view: HPDF_Dict, coox: HPDF_REAL, cooy: HPDF_REAL, cooz: HPDF_REAL, c2cx: HPDF_REAL, c2cy: HPDF_REAL, c2cz: HPDF_REAL, roo: HPDF_REAL, roll: HPDF_REAL) -> HPDF_STATUS;
+}
+
+/// Create a PDF text string object. Non-ASCII text is stored as UTF-16BE with a byte order mark.
+pub(crate) unsafe fn text_string_new(mmgr: HPDF_MMgr, text: &str) -> anyhow::Result<HPDF_HANDLE> {
+    let obj = if text.is_ascii() {
+        let text = std::ffi::CString::new(text)?;
+        HPDF_String_New(mmgr, text.as_ptr(), std::ptr::null_mut())
+    } else {
+        let mut buf = vec![0xFE, 0xFF];
+        for c in text.encode_utf16() {
+            buf.extend_from_slice(&c.to_be_bytes());
+        }
+        HPDF_Binary_New(mmgr, buf.as_ptr(), buf.len() as HPDF_UINT)
+    };
+
+    if obj.is_null() {
+        anyhow::bail!("failed to create string object");
+    }
+
+    Ok(obj)
+}
@@ -0,0 +1,226 @@
+//! ICC based color spaces and output intents.
+
+use crate::document::Document;
+use crate::ffi;
+
+/// ICC based color space handle type.
+pub struct IccProfile<'a> {
+    doc: &'a Document,
+    color_space: ffi::HPDF_Array,
+    components: usize,
+}
+
+impl<'a> IccProfile<'a> {
+    pub(crate) fn new(doc: &'a Document, color_space: ffi::HPDF_Array, components: usize) -> Self {
+        Self { doc, color_space, components }
+    }
+
+    /// `[/ICCBased stream]` array of the color space.
+    #[inline]
+    pub(crate) fn handle(&self) -> ffi::HPDF_Array {
+        self.doc.enter_memory();
+        self.color_space
+    }
+
+    /// Get the number of color components (1, 3 or 4).
+    pub fn components(&self) -> usize {
+        self.components
+    }
+}
+
+/// Subtype of output intent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputIntentSubtype {
+    /// GTS_PDFX
+    PdfX,
+
+    /// GTS_PDFA1
+    PdfA1,
+}
+
+impl OutputIntentSubtype {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::PdfX => "GTS_PDFX",
+            Self::PdfA1 => "GTS_PDFA1",
+        }
+    }
+}
+
+/// Output intent, the intended output device or production condition of the document.
+#[derive(Debug, Clone)]
+pub struct OutputIntent {
+    /// Subtype. Use `PdfA1` for PDF/A documents.
+    pub subtype: OutputIntentSubtype,
+
+    /// Identifier of the output condition, e.g. a name in the ICC characterization registry.
+    pub identifier: String,
+
+    /// Human readable description of the output condition.
+    pub condition: Option<String>,
+
+    /// URL of the registry in which the identifier is defined.
+    pub registry: Option<String>,
+
+    /// Additional information about the output condition.
+    pub info: Option<String>,
+}
+
+impl OutputIntent {
+    /// sRGB display output.
+    pub fn srgb() -> Self {
+        Self {
+            subtype: OutputIntentSubtype::PdfX,
+            identifier: "sRGB IEC61966-2.1".to_string(),
+            condition: None,
+            registry: Some("http://www.color.org".to_string()),
+            info: Some("sRGB IEC61966-2.1".to_string()),
+        }
+    }
+
+    /// Offset printing on coated paper (FOGRA39).
+    pub fn fogra39() -> Self {
+        Self {
+            subtype: OutputIntentSubtype::PdfX,
+            identifier: "FOGRA39".to_string(),
+            condition: Some("Offset commercial and specialty printing according to ISO 12647-2:2004 / Amd 1, paper type 1 or 2".to_string()),
+            registry: Some("http://www.color.org".to_string()),
+            info: Some("Coated FOGRA39 (ISO 12647-2:2004)".to_string()),
+        }
+    }
+}
+
+/// Check the header of an ICC profile against the number of color components.
+pub(crate) fn validate_profile(data: &[u8], components: usize) -> anyhow::Result<()> {
+    if data.len() < 128 || &data[36..40] != b"acsp" {
+        anyhow::bail!("invalid ICC profile");
+    }
+
+    let expected: &[u8] = match components {
+        1 => b"GRAY",
+        3 => b"RGB ",
+        _ => b"CMYK",
+    };
+
+    if &data[16..20] != expected {
+        anyhow::bail!("ICC profile color space \"{}\" does not have {} components", String::from_utf8_lossy(&data[16..20]), components);
+    }
+
+    Ok(())
+}
+
+/// Convert a value to ICC s15Fixed16Number.
+fn s15f16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+/// Append an ICC tag element, padded to a 4-byte boundary.
+fn push_tag(data: &mut Vec<u8>, tag: &[u8]) {
+    data.extend_from_slice(tag);
+    data.resize(data.len().div_ceil(4) * 4, 0);
+}
+
+/// XYZType tag.
+fn xyz_tag(x: f64, y: f64, z: f64) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    tag.extend_from_slice(&s15f16(x));
+    tag.extend_from_slice(&s15f16(y));
+    tag.extend_from_slice(&s15f16(z));
+    tag
+}
+
+/// textDescriptionType tag.
+fn desc_tag(text: &str) -> Vec<u8> {
+    let mut tag = b"desc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&(text.len() as u32 + 1).to_be_bytes());
+    tag.extend_from_slice(text.as_bytes());
+    tag.push(0);
+    tag.extend_from_slice(&[0; 4 + 4 + 2 + 1 + 67]);
+    tag
+}
+
+/// textType tag.
+fn text_tag(text: &str) -> Vec<u8> {
+    let mut tag = b"text\0\0\0\0".to_vec();
+    tag.extend_from_slice(text.as_bytes());
+    tag.push(0);
+    tag
+}
+
+/// curveType tag sampling the sRGB transfer function.
+fn srgb_curve_tag() -> Vec<u8> {
+    const COUNT: u32 = 1024;
+
+    let mut tag = b"curv\0\0\0\0".to_vec();
+    tag.extend_from_slice(&COUNT.to_be_bytes());
+    for i in 0..COUNT {
+        let x = i as f64 / (COUNT - 1) as f64;
+        let y = if x <= 0.04045 { x / 12.92 } else { ((x + 0.055) / 1.055).powf(2.4) };
+        tag.extend_from_slice(&((y * 65535.0).round() as u16).to_be_bytes());
+    }
+    tag
+}
+
+/// Build an ICC version 2 display profile of sRGB IEC61966-2.1.
+pub(crate) fn srgb_profile() -> Vec<u8> {
+    let curve = srgb_curve_tag();
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", desc_tag("sRGB IEC61966-2.1")),
+        (b"cprt", text_tag("No copyright, use freely")),
+        (b"wtpt", xyz_tag(0.9642, 1.0, 0.8249)),
+        (b"rXYZ", xyz_tag(0.4361, 0.2225, 0.0139)),
+        (b"gXYZ", xyz_tag(0.3851, 0.7169, 0.0971)),
+        (b"bXYZ", xyz_tag(0.1431, 0.0606, 0.7141)),
+        (b"rTRC", curve.clone()),
+        (b"gTRC", curve.clone()),
+        (b"bTRC", curve),
+    ];
+
+    /* tag data follows the header (128 bytes) and the tag table */
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut data = Vec::new();
+    let data_offset = 128 + 4 + 12 * tags.len();
+
+    let mut curve_offset = 0;
+
+    for (sig, tag) in &tags {
+        /* the three TRC tags share one curve */
+        let offset = match *sig {
+            b"gTRC" | b"bTRC" => curve_offset,
+            _ => {
+                let offset = data_offset + data.len();
+                push_tag(&mut data, tag);
+                offset
+            }
+        };
+
+        if *sig == b"rTRC" {
+            curve_offset = offset;
+        }
+
+        table.extend_from_slice(*sig);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+    }
+
+    let size = 128 + table.len() + data.len();
+
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0; 4]);                    /* preferred CMM */
+    profile.extend_from_slice(&[0x02, 0x10, 0x00, 0x00]);  /* version 2.1 */
+    profile.extend_from_slice(b"mntrRGB XYZ ");
+    for v in [2021u16, 1, 1, 0, 0, 0] {                    /* creation date */
+        profile.extend_from_slice(&v.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0; 4 + 4 + 4 + 4 + 8 + 4]);  /* platform .. rendering intent */
+    profile.extend_from_slice(&s15f16(0.9642));            /* PCS illuminant (D50) */
+    profile.extend_from_slice(&s15f16(1.0));
+    profile.extend_from_slice(&s15f16(0.8249));
+    profile.extend_from_slice(&[0; 4 + 16 + 28]);          /* creator, ID, reserved */
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+
+    profile
+}
@@ -1,19 +1,23 @@
 use crate::document::Document;
-use crate::Real;
+use crate::icc::IccProfile;
+use crate::{ffi, Real};
+
+use std::ffi::{CStr, CString};
 
 /// Image handle type.
 pub struct Image<'a> {
-    _doc: &'a Document,
+    doc: &'a Document,
     img: libharu_sys::HPDF_Image,
 }
 
 impl<'a> Image<'a> {
-    pub(crate) fn new(_doc: &'a Document, img: libharu_sys::HPDF_Destination) -> Self {
-        Self { _doc, img }
+    pub(crate) fn new(doc: &'a Document, img: libharu_sys::HPDF_Destination) -> Self {
+        Self { doc, img }
     }
     
     #[inline]
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Destination {
+        self.doc.enter_memory();
         self.img
     }
     
@@ -34,4 +38,66 @@ impl<'a> Image<'a> {
 
         Ok(ret as Real)
     }
+
+    /// Tag the image with an ICC based color space. The profile must have as many components
+    /// as the device color space of the image.
+    pub fn set_icc_profile(&self, profile: &IccProfile) -> anyhow::Result<()> {
+        let color_space = unsafe {
+            libharu_sys::HPDF_Image_GetColorSpace(self.handle())
+        };
+
+        if color_space.is_null() {
+            anyhow::bail!("HPDF_Image_GetColorSpace failed");
+        }
+
+        let components = match unsafe { CStr::from_ptr(color_space) }.to_str()? {
+            "DeviceGray" => 1,
+            "DeviceRGB" => 3,
+            "DeviceCMYK" => 4,
+            name => anyhow::bail!("image color space {} can not be tagged with an ICC profile", name),
+        };
+
+        if components != profile.components() {
+            anyhow::bail!("ICC profile has {} components, the image has {}", profile.components(), components);
+        }
+
+        let status = unsafe {
+            ffi::HPDF_Dict_Add(self.handle(), CString::new("ColorSpace")?.as_ptr(), profile.handle())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_Add failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+/// Check whether libharu would create a soft mask (alpha channel) for the PNG image.
+pub(crate) fn png_has_alpha(data: &[u8]) -> bool {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    if !data.starts_with(SIGNATURE) {
+        return false;
+    }
+
+    let mut pos = SIGNATURE.len();
+    let mut color_type = 0;
+
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let chunk = &data[pos + 4..pos + 8];
+
+        match chunk {
+            b"IHDR" if pos + 8 + 10 <= data.len() => color_type = data[pos + 8 + 9],
+            b"tRNS" => return color_type == 3,
+            b"IDAT" => break,
+            _ => {},
+        }
+
+        pos += 12 + len;
+    }
+
+    /* gray with alpha, or RGB with alpha */
+    color_type == 4 || color_type == 6
 }
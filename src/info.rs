@@ -0,0 +1,133 @@
+/// Attribute of the document info dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InfoType {
+    /// Date and time when the document was created. (date attribute)
+    CreationDate,
+
+    /// Date and time when the document was modified. (date attribute)
+    ModDate,
+
+    /// Name of the person who created the document.
+    Author,
+
+    /// Name of the application which created the original document.
+    Creator,
+
+    /// Name of the application which converted the document to PDF.
+    Producer,
+
+    /// Title of the document.
+    Title,
+
+    /// Subject of the document.
+    Subject,
+
+    /// Keywords of the document.
+    Keywords,
+}
+
+impl InfoType {
+    pub(crate) fn to_sys(self) -> libharu_sys::HPDF_InfoType {
+        match self {
+            InfoType::CreationDate => libharu_sys::HPDF_InfoType::HPDF_INFO_CREATION_DATE,
+            InfoType::ModDate => libharu_sys::HPDF_InfoType::HPDF_INFO_MOD_DATE,
+            InfoType::Author => libharu_sys::HPDF_InfoType::HPDF_INFO_AUTHOR,
+            InfoType::Creator => libharu_sys::HPDF_InfoType::HPDF_INFO_CREATOR,
+            InfoType::Producer => libharu_sys::HPDF_InfoType::HPDF_INFO_PRODUCER,
+            InfoType::Title => libharu_sys::HPDF_InfoType::HPDF_INFO_TITLE,
+            InfoType::Subject => libharu_sys::HPDF_InfoType::HPDF_INFO_SUBJECT,
+            InfoType::Keywords => libharu_sys::HPDF_InfoType::HPDF_INFO_KEYWORDS,
+        }
+    }
+
+    /// Key of the attribute in the info dictionary.
+    pub(crate) fn key(self) -> &'static str {
+        match self {
+            InfoType::CreationDate => "CreationDate",
+            InfoType::ModDate => "ModDate",
+            InfoType::Author => "Author",
+            InfoType::Creator => "Creator",
+            InfoType::Producer => "Producer",
+            InfoType::Title => "Title",
+            InfoType::Subject => "Subject",
+            InfoType::Keywords => "Keywords",
+        }
+    }
+
+    /// Whether the attribute holds a date.
+    pub(crate) fn is_date(self) -> bool {
+        matches!(self, InfoType::CreationDate | InfoType::ModDate)
+    }
+}
+
+/// Date and time of the document info dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InfoDate {
+    /// Year
+    pub year: i32,
+
+    /// Month (1-12)
+    pub month: u8,
+
+    /// Day (1-31)
+    pub day: u8,
+
+    /// Hour (0-23)
+    pub hour: u8,
+
+    /// Minutes (0-59)
+    pub minutes: u8,
+
+    /// Seconds (0-59)
+    pub seconds: u8,
+
+    /// Offset from UT in minutes. (`None` if unknown)
+    pub utc_offset: Option<i16>,
+}
+
+impl InfoDate {
+    pub(crate) fn to_ffi(self) -> libharu_sys::HPDF_Date {
+        let (ind, offset) = match self.utc_offset {
+            None => (b' ', 0),
+            Some(0) => (b'Z', 0),
+            Some(v) if v > 0 => (b'+', v),
+            Some(v) => (b'-', -v),
+        };
+
+        libharu_sys::HPDF_Date {
+            year: self.year,
+            month: self.month as _,
+            day: self.day as _,
+            hour: self.hour as _,
+            minutes: self.minutes as _,
+            seconds: self.seconds as _,
+            ind: ind as _,
+            off_hour: (offset / 60) as _,
+            off_minutes: (offset % 60) as _,
+        }
+    }
+
+    /// Format as an XMP date. (YYYY-MM-DDThh:mm:ss+hh:mm)
+    pub(crate) fn to_xmp(self) -> String {
+        let mut s = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minutes, self.seconds);
+
+        match self.utc_offset {
+            None => {},
+            Some(0) => s.push('Z'),
+            Some(v) => {
+                let sign = if v > 0 { '+' } else { '-' };
+                s += &format!("{}{:02}:{:02}", sign, v.abs() / 60, v.abs() % 60);
+            },
+        }
+
+        s
+    }
+}
+
+/// Values of the info dictionary kept on the Rust side, so that they can be read back as UTF-8.
+#[derive(Debug, Default)]
+pub(crate) struct InfoValues {
+    pub(crate) text: std::collections::HashMap<InfoType, String>,
+    pub(crate) dates: std::collections::HashMap<InfoType, InfoDate>,
+}
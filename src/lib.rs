@@ -9,6 +9,18 @@ mod encoder;
 mod error;
 mod context;
 mod image;
+mod annotation;
+mod toc;
+mod xobject;
+mod embedded_file;
+mod info;
+mod conformance;
+mod xmp;
+mod icc;
+mod memory;
+mod u3d;
+mod vertical;
+mod ffi;
 
 /// prelude
 pub mod prelude;
@@ -61,7 +73,7 @@ impl From<(Real, Real, Real, Real)> for CmykColor {
     }
 }
 /// Point
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Point {
     /// x
     pub x: Real,
@@ -79,7 +91,7 @@ impl From<(Real, Real)> for Point {
 }
 
 /// Rect
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Rect {
     /// Left position
     pub left: Real,
@@ -102,19 +114,187 @@ impl From<(Real, Real, Real, Real)> for Rect {
     }
 }
 
+impl From<(Length, Length)> for Point {
+    fn from(v: (Length, Length)) -> Self {
+        Self { x: v.0.points(), y: v.1.points() }
+    }
+}
+
+impl From<(Length, Length, Length, Length)> for Rect {
+    fn from(v: (Length, Length, Length, Length)) -> Self {
+        Self { left: v.0.points(), top: v.1.points(), right: v.2.points(), bottom: v.3.points() }
+    }
+}
+
+/// Length with a unit, converted into points (1/72 inch) which libharu uses for every coordinate.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct Length(Real);
+
+impl Copy for Length {}
+
+impl Length {
+    /// Length in points.
+    pub fn pt(value: Real) -> Self {
+        Self(value)
+    }
+
+    /// Length in inches.
+    pub fn inch(value: Real) -> Self {
+        Self(value * 72.0)
+    }
+
+    /// Length in millimeters.
+    pub fn mm(value: Real) -> Self {
+        Self(value * 72.0 / 25.4)
+    }
+
+    /// Length in centimeters.
+    pub fn cm(value: Real) -> Self {
+        Self::mm(value * 10.0)
+    }
+
+    /// Get the length in points.
+    pub fn points(self) -> Real {
+        self.0
+    }
+
+    /// Get the length in inches.
+    pub fn to_inch(self) -> Real {
+        self.0 / 72.0
+    }
+
+    /// Get the length in millimeters.
+    pub fn to_mm(self) -> Real {
+        self.0 * 25.4 / 72.0
+    }
+
+    /// Get the length in centimeters.
+    pub fn to_cm(self) -> Real {
+        self.to_mm() / 10.0
+    }
+}
+
+impl From<Length> for Real {
+    fn from(v: Length) -> Self {
+        v.points()
+    }
+}
+
+impl std::ops::Add for Length {
+    type Output = Length;
+    fn add(self, rhs: Length) -> Length {
+        Length(self.0 + rhs.0)
+    }
+}
+
+impl std::ops::Sub for Length {
+    type Output = Length;
+    fn sub(self, rhs: Length) -> Length {
+        Length(self.0 - rhs.0)
+    }
+}
+
+impl std::ops::Mul<Real> for Length {
+    type Output = Length;
+    fn mul(self, rhs: Real) -> Length {
+        Length(self.0 * rhs)
+    }
+}
+
+impl std::ops::Div<Real> for Length {
+    type Output = Length;
+    fn div(self, rhs: Real) -> Length {
+        Length(self.0 / rhs)
+    }
+}
+
+impl std::ops::Neg for Length {
+    type Output = Length;
+    fn neg(self) -> Length {
+        Length(-self.0)
+    }
+}
+
+/// Transformation matrix. (a b c d x y)
+#[derive(Debug, Clone)]
+pub struct TransMatrix {
+    /// a
+    pub a: Real,
+
+    /// b
+    pub b: Real,
+
+    /// c
+    pub c: Real,
+
+    /// d
+    pub d: Real,
+
+    /// x
+    pub x: Real,
+
+    /// y
+    pub y: Real,
+}
+
+impl Copy for TransMatrix {}
+
+impl TransMatrix {
+    /// Identity matrix.
+    pub fn identity() -> Self {
+        Self { a: 1.0, b: 0.0, c: 0.0, d: 1.0, x: 0.0, y: 0.0 }
+    }
+
+    /// Matrix which moves the origin to (x, y).
+    pub fn translate(x: Real, y: Real) -> Self {
+        Self { a: 1.0, b: 0.0, c: 0.0, d: 1.0, x, y }
+    }
+
+    /// Matrix which scales by (sx, sy).
+    pub fn scale(sx: Real, sy: Real) -> Self {
+        Self { a: sx, b: 0.0, c: 0.0, d: sy, x: 0.0, y: 0.0 }
+    }
+
+    /// Matrix which rotates counterclockwise by `angle` radians.
+    pub fn rotate(angle: Real) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self { a: cos, b: sin, c: -sin, d: cos, x: 0.0, y: 0.0 }
+    }
+
+    /// Matrix which applies `self` first, then `other`.
+    pub fn then(&self, other: &TransMatrix) -> Self {
+        Self {
+            a: self.a * other.a + self.b * other.c,
+            b: self.a * other.b + self.b * other.d,
+            c: self.c * other.a + self.d * other.c,
+            d: self.c * other.b + self.d * other.d,
+            x: self.x * other.a + self.y * other.c + other.x,
+            y: self.x * other.b + self.y * other.d + other.y,
+        }
+    }
+}
+
+impl From<(Real, Real, Real, Real, Real, Real)> for TransMatrix {
+    fn from(v: (Real, Real, Real, Real, Real, Real)) -> Self {
+        Self { a: v.0, b: v.1, c: v.2, d: v.3, x: v.4, y: v.5 }
+    }
+}
+
 /// Font handle type.
+#[derive(Clone)]
 pub struct Font<'a> {
     font: libharu_sys::HPDF_Font,
-    _doc: &'a prelude::Document,
+    doc: &'a prelude::Document,
 }
 
 impl<'a> Font<'a> {
-    pub(crate) fn new(_doc: &'a prelude::Document, font: libharu_sys::HPDF_Font) -> Self {
-        Self { font, _doc }
+    pub(crate) fn new(doc: &'a prelude::Document, font: libharu_sys::HPDF_Font) -> Self {
+        Self { font, doc }
     }
 
     #[inline]
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Font {
+        self.doc.enter_memory();
         self.font
     }
 
@@ -129,3 +309,62 @@ impl<'a> Font<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: Real, b: Real) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn length_converts_units() {
+        assert_near(Length::inch(1.0).points(), 72.0);
+        assert_near(Length::mm(25.4).points(), 72.0);
+        assert_near(Length::cm(2.54).points(), 72.0);
+        assert_near(Length::mm(210.0).points(), 595.276);
+
+        assert_near(Length::pt(72.0).to_inch(), 1.0);
+        assert_near(Length::pt(72.0).to_mm(), 25.4);
+        assert_near(Length::pt(72.0).to_cm(), 2.54);
+        assert_near(Length::mm(297.0).to_mm(), 297.0);
+    }
+
+    /// Transform a point as the PDF operators do: [x y 1] * matrix.
+    fn apply(m: &TransMatrix, x: Real, y: Real) -> (Real, Real) {
+        (m.a * x + m.c * y + m.x, m.b * x + m.d * y + m.y)
+    }
+
+    #[test]
+    fn trans_matrix_rotates_counterclockwise() {
+        let (x, y) = apply(&TransMatrix::rotate(std::f32::consts::FRAC_PI_2 as Real), 1.0, 0.0);
+        assert_near(x, 0.0);
+        assert_near(y, 1.0);
+    }
+
+    #[test]
+    fn trans_matrix_then_applies_self_first() {
+        let translate = TransMatrix::translate(10.0, 0.0);
+        let rotate = TransMatrix::rotate(std::f32::consts::FRAC_PI_2 as Real);
+
+        /* moved to (10, 0), then rotated onto the y axis */
+        let (x, y) = apply(&translate.then(&rotate), 0.0, 0.0);
+        assert_near(x, 0.0);
+        assert_near(y, 10.0);
+
+        /* rotated in place, then moved */
+        let (x, y) = apply(&rotate.then(&translate), 0.0, 0.0);
+        assert_near(x, 10.0);
+        assert_near(y, 0.0);
+
+        let m = TransMatrix::scale(2.0, 3.0).then(&translate);
+        assert_eq!(apply(&m, 1.0, 1.0), (12.0, 3.0));
+
+        let m = translate.then(&TransMatrix::scale(2.0, 3.0));
+        assert_eq!(apply(&m, 1.0, 1.0), (22.0, 3.0));
+
+        let m = TransMatrix::identity().then(&translate);
+        assert_eq!(apply(&m, 1.0, 1.0), apply(&translate, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,235 @@
+//! Memory accounting allocator passed to HPDF_NewEx.
+//!
+//! libharu calls the allocation functions without any user data (the user data of HPDF_NewEx is
+//! passed only to the error handler), so allocations are charged to the accounting context which
+//! was entered last on the current thread. Every handle of the crate enters the context of its
+//! document before it is passed to libharu, and a document is operated by one thread at a time.
+//! The context is recorded in the header of each block, so that the block is freed against the
+//! context which allocated it.
+//!
+//! Some error paths of libharu keep using an object after freeing it when an allocation fails,
+//! so once an allocation of a document has failed, the blocks it frees are held until the
+//! document is discarded instead of being returned to the system allocator.
+
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::os::raw::c_void;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Memory usage statistics of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Bytes currently allocated by libharu.
+    pub current: usize,
+
+    /// Largest number of bytes allocated at the same time.
+    pub peak: usize,
+
+    /// Memory budget of the document, if any.
+    pub limit: Option<usize>,
+}
+
+/// Size of the header placed in front of every allocation. (keeps 16 bytes alignment)
+///
+/// The header holds the size of the block and the context which allocated it.
+const HEADER_SIZE: usize = 16;
+
+const _: () = assert!(2 * std::mem::size_of::<usize>() <= HEADER_SIZE);
+
+struct Context {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+    limit: AtomicUsize,
+    exhausted: AtomicBool,
+    /// Addresses of the blocks freed after a failed allocation.
+    held: Mutex<HashSet<usize>>,
+}
+
+impl Context {
+    fn held(&self) -> MutexGuard<'_, HashSet<usize>> {
+        /* never panic in the allocation functions called from C */
+        self.held.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Return the held blocks to the system allocator.
+    fn release_held(&self) {
+        let held: Vec<usize> = self.held().drain().collect();
+        for addr in held {
+            unsafe { dealloc(addr as *mut u8) };
+        }
+    }
+}
+
+thread_local! {
+    /// Context charged by the allocations on this thread.
+    static CURRENT: Cell<Option<Arc<Context>>> = const { Cell::new(None) };
+}
+
+pub(crate) type AllocFunc = unsafe extern "C" fn(size: libharu_sys::HPDF_UINT) -> *mut c_void;
+pub(crate) type FreeFunc = unsafe extern "C" fn(ptr: *mut c_void);
+
+/// Get the context entered last on this thread.
+fn current() -> Option<Arc<Context>> {
+    /* the thread local may already be destroyed while the thread exits */
+    CURRENT.try_with(|current| {
+        let context = current.take();
+        current.set(context.clone());
+        context
+    }).ok().flatten()
+}
+
+/// Read the header of a block.
+unsafe fn header(ptr: *mut u8) -> (usize, *const Context) {
+    let header = ptr as *const usize;
+    (header.read(), header.add(1).read() as *const Context)
+}
+
+/// Return a block to the system allocator, with the reference to its context.
+unsafe fn dealloc(ptr: *mut u8) {
+    let (size, context) = header(ptr);
+    std::alloc::dealloc(ptr, Layout::from_size_align_unchecked(size + HEADER_SIZE, HEADER_SIZE));
+    if !context.is_null() {
+        drop(Arc::from_raw(context));
+    }
+}
+
+unsafe extern "C" fn alloc(size: libharu_sys::HPDF_UINT) -> *mut c_void {
+    let size = size as usize;
+    let layout = match Layout::from_size_align(size + HEADER_SIZE, HEADER_SIZE) {
+        Ok(layout) => layout,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let context = current();
+    let mut reserved = 0;
+    if let Some(context) = &context {
+        /* reserve the bytes first, so that concurrent allocations can not exceed the budget */
+        reserved = context.current.fetch_add(size, Ordering::SeqCst) + size;
+        if reserved > context.limit.load(Ordering::SeqCst) {
+            context.current.fetch_sub(size, Ordering::SeqCst);
+            context.exhausted.store(true, Ordering::SeqCst);
+            return std::ptr::null_mut();
+        }
+    }
+
+    let ptr = std::alloc::alloc(layout);
+    if ptr.is_null() {
+        if let Some(context) = &context {
+            context.current.fetch_sub(size, Ordering::SeqCst);
+            context.exhausted.store(true, Ordering::SeqCst);
+        }
+        return std::ptr::null_mut();
+    }
+
+    if let Some(context) = &context {
+        context.peak.fetch_max(reserved, Ordering::SeqCst);
+    }
+
+    /* the block keeps its context alive until it is returned to the system allocator */
+    let header = ptr as *mut usize;
+    header.write(size);
+    header.add(1).write(context.map_or(std::ptr::null(), Arc::into_raw) as usize);
+    ptr.add(HEADER_SIZE) as *mut c_void
+}
+
+unsafe extern "C" fn free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+
+    let ptr = (ptr as *mut u8).sub(HEADER_SIZE);
+    let (size, context) = header(ptr);
+
+    if let Some(context) = context.as_ref() {
+        if context.exhausted.load(Ordering::SeqCst) {
+            /* the same error paths may free a block twice */
+            if context.held().insert(ptr as usize) {
+                context.current.fetch_sub(size, Ordering::SeqCst);
+            }
+            return;
+        }
+
+        context.current.fetch_sub(size, Ordering::SeqCst);
+    }
+
+    dealloc(ptr);
+}
+
+/// Memory accounting of one document.
+pub(crate) struct Accounting {
+    context: Arc<Context>,
+}
+
+impl Accounting {
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        let context = Context {
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            limit: AtomicUsize::new(limit.unwrap_or(usize::MAX)),
+            exhausted: AtomicBool::new(false),
+            held: Mutex::new(HashSet::new()),
+        };
+
+        Self { context: Arc::new(context) }
+    }
+
+    /// Allocation functions passed to HPDF_NewEx.
+    pub(crate) fn functions(&self) -> (AllocFunc, FreeFunc) {
+        (alloc, free)
+    }
+
+    /// Charge the allocations on this thread to the document, until another context is entered.
+    #[inline]
+    pub(crate) fn enter(&self) {
+        let _ = CURRENT.try_with(|current| {
+            let entered = current.take();
+            match entered {
+                Some(entered) if Arc::ptr_eq(&entered, &self.context) => current.set(Some(entered)),
+                _ => current.set(Some(self.context.clone())),
+            }
+        });
+    }
+
+    /// Check whether an allocation has failed since the last `clear_exhausted`.
+    ///
+    /// libharu does not always report a failed allocation as HPDF_FAILD_TO_ALLOC_MEM,
+    /// so the failure is recorded by the allocator itself.
+    pub(crate) fn exhausted(&self) -> bool {
+        self.context.exhausted.load(Ordering::SeqCst)
+    }
+
+    /// Forget the failed allocations and release the blocks held since, after the document was discarded.
+    pub(crate) fn clear_exhausted(&self) {
+        self.context.exhausted.store(false, Ordering::SeqCst);
+        self.context.release_held();
+    }
+
+    /// Get the statistics of the document.
+    pub(crate) fn stats(&self) -> MemoryStats {
+        let context = &self.context;
+        let limit = context.limit.load(Ordering::SeqCst);
+
+        MemoryStats {
+            current: context.current.load(Ordering::SeqCst),
+            peak: context.peak.load(Ordering::SeqCst),
+            limit: if limit == usize::MAX { None } else { Some(limit) },
+        }
+    }
+}
+
+impl Drop for Accounting {
+    fn drop(&mut self) {
+        /* the document has been freed before */
+        self.context.release_held();
+
+        let _ = CURRENT.try_with(|current| {
+            if let Some(entered) = current.take() {
+                if !Arc::ptr_eq(&entered, &self.context) {
+                    current.set(Some(entered));
+                }
+            }
+        });
+    }
+}
@@ -1,19 +1,42 @@
 use crate::document::Document;
 use crate::destination::Destination;
+use crate::encoder::Encoder;
+use crate::ffi;
+use crate::Color;
+
+use bitflags::bitflags;
+
+use std::borrow::Cow;
+use std::ffi::CString;
+
+bitflags! {
+    /// The flags specifying the style of outline title.
+    pub struct OutlineStyle: u32 {
+        /// Normal text.
+        const NORMAL = 0x00;
+
+        /// Italic text.
+        const ITALIC = 0x01;
+
+        /// Bold text.
+        const BOLD   = 0x02;
+    }
+}
 
 /// Outline handle type.
 pub struct Outline<'a> {
     outline: libharu_sys::HPDF_Outline,
-    _doc: &'a Document,
+    doc: &'a Document,
 }
 
 impl<'a> Outline<'a> {
-    pub(crate) fn new(_doc: &'a Document, outline: libharu_sys::HPDF_Outline) -> Self {
-        Self { _doc, outline }
+    pub(crate) fn new(doc: &'a Document, outline: libharu_sys::HPDF_Outline) -> Self {
+        Self { doc, outline }
     }
 
     #[inline]
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Outline {
+        self.doc.enter_memory();
         self.outline
     }
 
@@ -49,4 +72,288 @@ impl<'a> Outline<'a> {
 
         Ok(())
     }
+
+    /// Set the color of the outline title.
+    pub fn set_color<T>(&self, color: T) -> anyhow::Result<()>
+    where
+        T: Into<Color>
+    {
+        let color = color.into();
+        let key = CString::new("C")?;
+
+        unsafe {
+            let array = ffi::HPDF_Array_New(ffi::dict_mmgr(self.handle()));
+            if array.is_null() {
+                anyhow::bail!("HPDF_Array_New failed");
+            }
+
+            for value in [color.red, color.green, color.blue] {
+                ffi::check(ffi::HPDF_Array_AddReal(array, value), "HPDF_Array_AddReal")?;
+            }
+            ffi::check(ffi::HPDF_Dict_Add(self.handle(), key.as_ptr(), array), "HPDF_Dict_Add")?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the style of the outline title.
+    pub fn set_style(&self, style: OutlineStyle) -> anyhow::Result<()> {
+        let key = CString::new("F")?;
+
+        let status = unsafe {
+            ffi::HPDF_Dict_AddNumber(self.handle(), key.as_ptr(), style.bits() as libharu_sys::HPDF_INT32)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Dict_AddNumber failed (status={})", status);
+        }
+
+        Ok(())
+    }
+}
+
+/// Title of outline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutlineTitle<'a> {
+    /// Text, which is converted into the encoding of the encoder like `show_text`.
+    Text(Cow<'a, str>),
+
+    /// Bytes in the encoding of the encoder, which are passed to libharu as is.
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl<'a> OutlineTitle<'a> {
+    /// Get the title as bytes, without conversion.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Bytes(bytes) => bytes,
+        }
+    }
+
+    /// Get the owned title.
+    pub fn into_owned(self) -> OutlineTitle<'static> {
+        match self {
+            Self::Text(text) => OutlineTitle::Text(Cow::Owned(text.into_owned())),
+            Self::Bytes(bytes) => OutlineTitle::Bytes(Cow::Owned(bytes.into_owned())),
+        }
+    }
+
+    /// Get the bytes passed to libharu with the encoder.
+    pub(crate) fn encode(&self, enc: Option<&Encoder>) -> anyhow::Result<Cow<'_, [u8]>> {
+        match (self, enc) {
+            (Self::Text(text), Some(enc)) => Ok(crate::encoder::encode_str(enc.name()?, text)?),
+            _ => Ok(Cow::Borrowed(self.as_bytes())),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for OutlineTitle<'a> {
+    fn from(text: &'a str) -> Self {
+        Self::Text(Cow::Borrowed(text))
+    }
+}
+
+impl<'a> From<&'a String> for OutlineTitle<'a> {
+    fn from(text: &'a String) -> Self {
+        Self::Text(Cow::Borrowed(text))
+    }
+}
+
+impl From<String> for OutlineTitle<'_> {
+    fn from(text: String) -> Self {
+        Self::Text(Cow::Owned(text))
+    }
+}
+
+impl<'a> From<&'a [u8]> for OutlineTitle<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        Self::Bytes(Cow::Borrowed(bytes))
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for OutlineTitle<'a> {
+    fn from(bytes: &'a [u8; N]) -> Self {
+        Self::Bytes(Cow::Borrowed(bytes))
+    }
+}
+
+impl<'a> From<&'a Vec<u8>> for OutlineTitle<'a> {
+    fn from(bytes: &'a Vec<u8>) -> Self {
+        Self::Bytes(Cow::Borrowed(bytes))
+    }
+}
+
+impl From<Vec<u8>> for OutlineTitle<'_> {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(Cow::Owned(bytes))
+    }
+}
+
+/// Node of [`OutlineTree`].
+pub struct OutlineNode<'doc> {
+    pub(crate) title: OutlineTitle<'static>,
+    pub(crate) dst: Option<(&'doc Document, libharu_sys::HPDF_Destination)>,
+    opened: bool,
+    color: Option<Color>,
+    style: OutlineStyle,
+    children: Vec<OutlineNode<'doc>>,
+}
+
+impl<'doc> OutlineNode<'doc> {
+    /// Create a new outline node. The title is encoded by the encoder passed to
+    /// [`Document::create_outline_tree`].
+    pub fn new<'t, T>(title: T) -> Self
+    where
+        T: Into<OutlineTitle<'t>>
+    {
+        Self {
+            title: title.into().into_owned(),
+            dst: None,
+            opened: false,
+            color: None,
+            style: OutlineStyle::NORMAL,
+            children: Vec::new(),
+        }
+    }
+
+    /// Get the title of the node.
+    pub fn title(&self) -> &[u8] {
+        self.title.as_bytes()
+    }
+
+    /// Set the title of the node.
+    pub fn set_title<'t, T>(&mut self, title: T)
+    where
+        T: Into<OutlineTitle<'t>>
+    {
+        self.title = title.into().into_owned();
+    }
+
+    /// Return true if the node has a destination.
+    pub fn has_destination(&self) -> bool {
+        self.dst.is_some()
+    }
+
+    /// Set a destination which becomes to a target to jump when the outline is clicked.
+    pub fn set_destination(&mut self, dst: &Destination<'_, 'doc>) {
+        self.dst = Some((dst.doc(), dst.handle()));
+    }
+
+    /// Return true if the node is opened when the outline is displayed for the first time.
+    pub fn is_opened(&self) -> bool {
+        self.opened
+    }
+
+    /// Set whether this node is opened or not when the outline is displayed for the first time.
+    pub fn set_opened(&mut self, opened: bool) {
+        self.opened = opened;
+    }
+
+    /// Get the color of the title.
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// Set the color of the title.
+    pub fn set_color<T>(&mut self, color: T)
+    where
+        T: Into<Color>
+    {
+        self.color = Some(color.into());
+    }
+
+    /// Get the style of the title.
+    pub fn style(&self) -> OutlineStyle {
+        self.style
+    }
+
+    /// Set the style of the title.
+    pub fn set_style(&mut self, style: OutlineStyle) {
+        self.style = style;
+    }
+
+    /// Append a child node and return a reference to it.
+    pub fn add_child(&mut self, node: OutlineNode<'doc>) -> &mut OutlineNode<'doc> {
+        self.children.push(node);
+        self.children.last_mut().unwrap()
+    }
+
+    /// Get the child nodes.
+    pub fn children(&self) -> &[OutlineNode<'doc>] {
+        &self.children
+    }
+
+    /// Get the child nodes. The nodes can be reordered, inserted or removed.
+    pub fn children_mut(&mut self) -> &mut Vec<OutlineNode<'doc>> {
+        &mut self.children
+    }
+}
+
+/// Outline tree built in Rust, and created in a document by [`Document::create_outline_tree`].
+#[derive(Default)]
+pub struct OutlineTree<'doc> {
+    nodes: Vec<OutlineNode<'doc>>,
+}
+
+impl<'doc> OutlineTree<'doc> {
+    /// Create a new empty outline tree.
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    /// Append a top level node and return a reference to it.
+    pub fn add(&mut self, node: OutlineNode<'doc>) -> &mut OutlineNode<'doc> {
+        self.nodes.push(node);
+        self.nodes.last_mut().unwrap()
+    }
+
+    /// Get the top level nodes.
+    pub fn nodes(&self) -> &[OutlineNode<'doc>] {
+        &self.nodes
+    }
+
+    /// Get the top level nodes. The nodes can be reordered, inserted or removed.
+    pub fn nodes_mut(&mut self) -> &mut Vec<OutlineNode<'doc>> {
+        &mut self.nodes
+    }
+
+    /// Get the number of all nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Return true if the tree has no node.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Iterate all nodes in depth-first order with their depth. (The depth of a top level node is 0.)
+    pub fn iter(&self) -> OutlineTreeIter<'_, 'doc> {
+        OutlineTreeIter { stack: vec![self.nodes.iter()] }
+    }
+}
+
+/// Depth-first iterator over [`OutlineTree`].
+pub struct OutlineTreeIter<'a, 'doc> {
+    stack: Vec<std::slice::Iter<'a, OutlineNode<'doc>>>,
+}
+
+impl<'a, 'doc> Iterator for OutlineTreeIter<'a, 'doc> {
+    type Item = (usize, &'a OutlineNode<'doc>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let depth = self.stack.len().checked_sub(1)?;
+            match self.stack[depth].next() {
+                Some(node) => {
+                    self.stack.push(node.children.iter());
+                    return Some((depth, node));
+                },
+                None => {
+                    self.stack.pop();
+                },
+            }
+        }
+    }
 }
@@ -1,6 +1,13 @@
 use crate::prelude::*;
 
-use std::ffi::CString;
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+
+/// Length of crop marks drawn by `Page::set_bleed_with_crop_marks`.
+const CROP_MARK_LENGTH: Real = 18.0;
+
+/// Line width of crop marks drawn by `Page::set_bleed_with_crop_marks`.
+const CROP_MARK_WIDTH: Real = 0.25;
 
 /// The style of line-cap.
 #[derive(Debug)]
@@ -57,7 +64,9 @@ pub enum TextRenderingMode {
 }
 
 /// Size of page.
-#[derive(Debug)]
+///
+/// `Letter` is also known as ANSI A, and `B4`/`B5` are the ISO B sizes.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PageSize {
     /// 8½ x 11 (Inches), 612 x 792 (pixel)
     Letter,
@@ -65,7 +74,7 @@ pub enum PageSize {
     /// 8 ½ x 14 (Inches), 612 x 1008 (pixel)
     Legal,
 
-    /// 297 × 420 (mm), 841.89 x 1199.551 (pixel)
+    /// 297 × 420 (mm), 841.89 x 1190.551 (pixel)
     A3,
 
     /// 210 × 297 (mm), 595.276 x 841.89 (pixel)
@@ -94,10 +103,286 @@ pub enum PageSize {
 
     /// 4.125 x 9.5 (Inches), 297x 684 (pixel)
     Comm10,
+
+    /// ISO A0, 841 × 1189 (mm)
+    A0,
+
+    /// ISO A1, 594 × 841 (mm)
+    A1,
+
+    /// ISO A2, 420 × 594 (mm)
+    A2,
+
+    /// ISO A6, 105 × 148 (mm)
+    A6,
+
+    /// ISO A7, 74 × 105 (mm)
+    A7,
+
+    /// ISO A8, 52 × 74 (mm)
+    A8,
+
+    /// ISO A9, 37 × 52 (mm)
+    A9,
+
+    /// ISO A10, 26 × 37 (mm)
+    A10,
+
+    /// ISO B0, 1000 × 1414 (mm)
+    B0,
+
+    /// ISO B1, 707 × 1000 (mm)
+    B1,
+
+    /// ISO B2, 500 × 707 (mm)
+    B2,
+
+    /// ISO B3, 353 × 500 (mm)
+    B3,
+
+    /// ISO B6, 125 × 176 (mm)
+    B6,
+
+    /// ISO B7, 88 × 125 (mm)
+    B7,
+
+    /// ISO B8, 62 × 88 (mm)
+    B8,
+
+    /// ISO B9, 44 × 62 (mm)
+    B9,
+
+    /// ISO B10, 31 × 44 (mm)
+    B10,
+
+    /// ISO C0, 917 × 1297 (mm)
+    C0,
+
+    /// ISO C1, 648 × 917 (mm)
+    C1,
+
+    /// ISO C2, 458 × 648 (mm)
+    C2,
+
+    /// ISO C3, 324 × 458 (mm)
+    C3,
+
+    /// ISO C4, 229 × 324 (mm)
+    C4,
+
+    /// ISO C5, 162 × 229 (mm)
+    C5,
+
+    /// ISO C6, 114 × 162 (mm)
+    C6,
+
+    /// ISO C7, 81 × 114 (mm)
+    C7,
+
+    /// ISO C8, 57 × 81 (mm)
+    C8,
+
+    /// ISO C9, 40 × 57 (mm)
+    C9,
+
+    /// ISO C10, 28 × 40 (mm)
+    C10,
+
+    /// JIS B0, 1030 × 1456 (mm)
+    JisB0,
+
+    /// JIS B1, 728 × 1030 (mm)
+    JisB1,
+
+    /// JIS B2, 515 × 728 (mm)
+    JisB2,
+
+    /// JIS B3, 364 × 515 (mm)
+    JisB3,
+
+    /// JIS B4, 257 × 364 (mm)
+    JisB4,
+
+    /// JIS B5, 182 × 257 (mm)
+    JisB5,
+
+    /// JIS B6, 128 × 182 (mm)
+    JisB6,
+
+    /// JIS B7, 91 × 128 (mm)
+    JisB7,
+
+    /// JIS B8, 64 × 91 (mm)
+    JisB8,
+
+    /// JIS B9, 45 × 64 (mm)
+    JisB9,
+
+    /// JIS B10, 32 × 45 (mm)
+    JisB10,
+
+    /// ISO DL envelope, 110 × 220 (mm)
+    Dl,
+
+    /// ANSI B, 11 x 17 (Inches)
+    AnsiB,
+
+    /// ANSI C, 17 x 22 (Inches)
+    AnsiC,
+
+    /// ANSI D, 22 x 34 (Inches)
+    AnsiD,
+
+    /// ANSI E, 34 x 44 (Inches)
+    AnsiE,
+
+    /// US #9 envelope, 3.875 x 8.875 (Inches)
+    Comm9,
+
+    /// US Monarch envelope, 3.875 x 7.5 (Inches)
+    Monarch,
+
+    /// US A2 envelope, 4.375 x 5.75 (Inches)
+    EnvelopeA2,
+
+    /// US A6 envelope, 4.75 x 6.5 (Inches)
+    EnvelopeA6,
+
+    /// US A7 envelope, 5.25 x 7.25 (Inches)
+    EnvelopeA7,
+
+    /// US 6 x 9 envelope, 6 x 9 (Inches)
+    Envelope6x9,
+
+    /// US 9 x 12 envelope, 9 x 12 (Inches)
+    Envelope9x12,
+
+    /// US 10 x 13 envelope, 10 x 13 (Inches)
+    Envelope10x13,
+
+    /// Arbitrary size. The width and height are used as given, whatever the direction.
+    Custom {
+        /// Width of page
+        width: Length,
+
+        /// Height of page
+        height: Length,
+    },
+}
+
+/// Smallest width and height of a page. (HPDF_MIN_PAGESIZE)
+const MIN_PAGE_SIZE: Real = 3.0;
+
+/// Largest width and height of a page. (HPDF_MAX_PAGESIZE)
+const MAX_PAGE_SIZE: Real = 14400.0;
+
+/// Named sizes in the order `Page::size` looks them up.
+const PAGE_SIZE_PRESETS: [PageSize; 64] = [
+    PageSize::Letter, PageSize::Legal, PageSize::A3, PageSize::A4, PageSize::A5, PageSize::B4, PageSize::B5,
+    PageSize::Executive, PageSize::US4x6, PageSize::US4x8, PageSize::US5x7, PageSize::Comm10,
+    PageSize::A0, PageSize::A1, PageSize::A2, PageSize::A6, PageSize::A7, PageSize::A8, PageSize::A9, PageSize::A10,
+    PageSize::B0, PageSize::B1, PageSize::B2, PageSize::B3, PageSize::B6, PageSize::B7, PageSize::B8, PageSize::B9,
+    PageSize::B10, PageSize::C0, PageSize::C1, PageSize::C2, PageSize::C3, PageSize::C4, PageSize::C5, PageSize::C6,
+    PageSize::C7, PageSize::C8, PageSize::C9, PageSize::C10, PageSize::JisB0, PageSize::JisB1, PageSize::JisB2, PageSize::JisB3,
+    PageSize::JisB4, PageSize::JisB5, PageSize::JisB6, PageSize::JisB7, PageSize::JisB8, PageSize::JisB9, PageSize::JisB10, PageSize::Dl,
+    PageSize::AnsiB, PageSize::AnsiC, PageSize::AnsiD, PageSize::AnsiE, PageSize::Comm9, PageSize::Monarch, PageSize::EnvelopeA2, PageSize::EnvelopeA6,
+    PageSize::EnvelopeA7, PageSize::Envelope6x9, PageSize::Envelope9x12, PageSize::Envelope10x13,
+];
+
+impl PageSize {
+    /// Get the width and height of the size in portrait direction. A custom size is returned as given.
+    pub fn dimensions(self) -> (Length, Length) {
+        match self {
+            Self::Letter => (Length::pt(612.0), Length::pt(792.0)),
+            Self::Legal => (Length::pt(612.0), Length::pt(1008.0)),
+            Self::A3 => (Length::pt(841.89), Length::pt(1190.551)),
+            Self::A4 => (Length::pt(595.276), Length::pt(841.89)),
+            Self::A5 => (Length::pt(419.528), Length::pt(595.276)),
+            Self::B4 => (Length::pt(708.661), Length::pt(1000.63)),
+            Self::B5 => (Length::pt(498.898), Length::pt(708.661)),
+            Self::Executive => (Length::pt(522.0), Length::pt(756.0)),
+            Self::US4x6 => (Length::pt(288.0), Length::pt(432.0)),
+            Self::US4x8 => (Length::pt(288.0), Length::pt(576.0)),
+            Self::US5x7 => (Length::pt(360.0), Length::pt(504.0)),
+            Self::Comm10 => (Length::pt(297.0), Length::pt(684.0)),
+            Self::A0 => (Length::mm(841.0), Length::mm(1189.0)),
+            Self::A1 => (Length::mm(594.0), Length::mm(841.0)),
+            Self::A2 => (Length::mm(420.0), Length::mm(594.0)),
+            Self::A6 => (Length::mm(105.0), Length::mm(148.0)),
+            Self::A7 => (Length::mm(74.0), Length::mm(105.0)),
+            Self::A8 => (Length::mm(52.0), Length::mm(74.0)),
+            Self::A9 => (Length::mm(37.0), Length::mm(52.0)),
+            Self::A10 => (Length::mm(26.0), Length::mm(37.0)),
+            Self::B0 => (Length::mm(1000.0), Length::mm(1414.0)),
+            Self::B1 => (Length::mm(707.0), Length::mm(1000.0)),
+            Self::B2 => (Length::mm(500.0), Length::mm(707.0)),
+            Self::B3 => (Length::mm(353.0), Length::mm(500.0)),
+            Self::B6 => (Length::mm(125.0), Length::mm(176.0)),
+            Self::B7 => (Length::mm(88.0), Length::mm(125.0)),
+            Self::B8 => (Length::mm(62.0), Length::mm(88.0)),
+            Self::B9 => (Length::mm(44.0), Length::mm(62.0)),
+            Self::B10 => (Length::mm(31.0), Length::mm(44.0)),
+            Self::C0 => (Length::mm(917.0), Length::mm(1297.0)),
+            Self::C1 => (Length::mm(648.0), Length::mm(917.0)),
+            Self::C2 => (Length::mm(458.0), Length::mm(648.0)),
+            Self::C3 => (Length::mm(324.0), Length::mm(458.0)),
+            Self::C4 => (Length::mm(229.0), Length::mm(324.0)),
+            Self::C5 => (Length::mm(162.0), Length::mm(229.0)),
+            Self::C6 => (Length::mm(114.0), Length::mm(162.0)),
+            Self::C7 => (Length::mm(81.0), Length::mm(114.0)),
+            Self::C8 => (Length::mm(57.0), Length::mm(81.0)),
+            Self::C9 => (Length::mm(40.0), Length::mm(57.0)),
+            Self::C10 => (Length::mm(28.0), Length::mm(40.0)),
+            Self::JisB0 => (Length::mm(1030.0), Length::mm(1456.0)),
+            Self::JisB1 => (Length::mm(728.0), Length::mm(1030.0)),
+            Self::JisB2 => (Length::mm(515.0), Length::mm(728.0)),
+            Self::JisB3 => (Length::mm(364.0), Length::mm(515.0)),
+            Self::JisB4 => (Length::mm(257.0), Length::mm(364.0)),
+            Self::JisB5 => (Length::mm(182.0), Length::mm(257.0)),
+            Self::JisB6 => (Length::mm(128.0), Length::mm(182.0)),
+            Self::JisB7 => (Length::mm(91.0), Length::mm(128.0)),
+            Self::JisB8 => (Length::mm(64.0), Length::mm(91.0)),
+            Self::JisB9 => (Length::mm(45.0), Length::mm(64.0)),
+            Self::JisB10 => (Length::mm(32.0), Length::mm(45.0)),
+            Self::Dl => (Length::mm(110.0), Length::mm(220.0)),
+            Self::AnsiB => (Length::inch(11.0), Length::inch(17.0)),
+            Self::AnsiC => (Length::inch(17.0), Length::inch(22.0)),
+            Self::AnsiD => (Length::inch(22.0), Length::inch(34.0)),
+            Self::AnsiE => (Length::inch(34.0), Length::inch(44.0)),
+            Self::Comm9 => (Length::inch(3.875), Length::inch(8.875)),
+            Self::Monarch => (Length::inch(3.875), Length::inch(7.5)),
+            Self::EnvelopeA2 => (Length::inch(4.375), Length::inch(5.75)),
+            Self::EnvelopeA6 => (Length::inch(4.75), Length::inch(6.5)),
+            Self::EnvelopeA7 => (Length::inch(5.25), Length::inch(7.25)),
+            Self::Envelope6x9 => (Length::inch(6.0), Length::inch(9.0)),
+            Self::Envelope9x12 => (Length::inch(9.0), Length::inch(12.0)),
+            Self::Envelope10x13 => (Length::inch(10.0), Length::inch(13.0)),
+            Self::Custom { width, height } => (width, height),
+        }
+    }
+
+    /// Get the libharu preset of the size, if it has.
+    fn preset(self) -> Option<libharu_sys::HPDF_PageSizes> {
+        Some(match self {
+            Self::Letter => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_LETTER,
+            Self::Legal => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_LEGAL,
+            Self::A3 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_A3,
+            Self::A4 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_A4,
+            Self::A5 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_A5,
+            Self::B4 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_B4,
+            Self::B5 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_B5,
+            Self::Executive => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_EXECUTIVE,
+            Self::US4x6 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_US4x6,
+            Self::US4x8 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_US4x8,
+            Self::US5x7 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_US5x7,
+            Self::Comm10 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_COMM10,
+            _ => return None,
+        })
+    }
 }
 
 /// Direction of page.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PageDirection {
     /// longer value to horizontal
     Portrait,
@@ -122,7 +407,90 @@ pub enum TextAlignment {
     Justify,
 }
 
+/// Boundary box of page, other than the media box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoxType {
+    /// Region to which the contents are clipped when the page is displayed or printed.
+    Crop,
+
+    /// Region to which the contents are clipped in production, including the bleed area.
+    Bleed,
+
+    /// Dimensions of the finished page after trimming.
+    Trim,
+
+    /// Extent of the meaningful contents of the page.
+    Art,
+}
+
+impl BoxType {
+    fn key(self) -> &'static str {
+        match self {
+            Self::Crop => "CropBox",
+            Self::Bleed => "BleedBox",
+            Self::Trim => "TrimBox",
+            Self::Art => "ArtBox",
+        }
+    }
+}
+
+/// Transition style of slide show.
+#[derive(Debug)]
+pub enum Transition {
+    /// Wipe from left to right.
+    WipeRight,
+
+    /// Wipe from bottom to top.
+    WipeUp,
+
+    /// Wipe from right to left.
+    WipeLeft,
+
+    /// Wipe from top to bottom.
+    WipeDown,
+
+    /// Split horizontally from the center to the edges.
+    BarnDoorsHorizontalOut,
+
+    /// Split horizontally from the edges to the center.
+    BarnDoorsHorizontalIn,
+
+    /// Split vertically from the center to the edges.
+    BarnDoorsVerticalOut,
+
+    /// Split vertically from the edges to the center.
+    BarnDoorsVerticalIn,
+
+    /// Box sweeps from the center to the edges.
+    BoxOut,
+
+    /// Box sweeps from the edges to the center.
+    BoxIn,
+
+    /// Horizontal blinds.
+    BlindsHorizontal,
+
+    /// Vertical blinds.
+    BlindsVertical,
+
+    /// The old page dissolves to reveal the new page.
+    Dissolve,
+
+    /// Glitter from left to right.
+    GlitterRight,
+
+    /// Glitter from top to bottom.
+    GlitterDown,
+
+    /// Glitter from top-left to bottom-right.
+    GlitterTopLeftToBottomRight,
+
+    /// The new page simply replaces the old page.
+    Replace,
+}
+
 /// Page handle type.
+#[derive(Clone)]
 pub struct Page<'a> {
     page: libharu_sys::HPDF_Page,
     doc: &'a Document,
@@ -137,9 +505,16 @@ impl<'a> Page<'a> {
     /// Get internal handle.
     #[inline]
     pub(crate) fn handle(&self) -> libharu_sys::HPDF_Page {
+        self.doc.enter_memory();
         self.page
     }
 
+    /// Get the document which the page belongs to.
+    #[inline]
+    pub(crate) fn doc(&self) -> &'a Document {
+        self.doc
+    }
+
     /// Get height of page.
     pub fn height(&self) -> anyhow::Result<Real> {
         let ret = unsafe {
@@ -217,8 +592,26 @@ impl<'a> Page<'a> {
         Ok(())
     }
 
+    /// Concatenate the matrix to the page's current transformation matrix.
+    pub fn concat<T>(&self, matrix: T) -> anyhow::Result<()>
+    where
+        T: Into<TransMatrix>
+    {
+        let m = matrix.into();
+
+        let status = unsafe {
+            libharu_sys::HPDF_Page_Concat(self.handle(), m.a, m.b, m.c, m.d, m.x, m.y)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Page_Concat failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
     /// Gets the handle of the page's current font.
-    pub fn current_font(&self) -> anyhow::Result<Font> {
+    pub fn current_font(&self) -> anyhow::Result<Font<'_>> {
         let font = unsafe {
             libharu_sys::HPDF_Page_GetCurrentFont(self.handle())
         };
@@ -230,6 +623,32 @@ impl<'a> Page<'a> {
         Ok(Font::new(self.doc, font))
     }
 
+    /// Get the encoding name of the page's current font, or an empty string without font.
+    fn current_encoding_name(&self) -> anyhow::Result<&str> {
+        let name = unsafe {
+            let font = libharu_sys::HPDF_Page_GetCurrentFont(self.handle());
+            if font.is_null() {
+                return Ok("");
+            }
+
+            libharu_sys::HPDF_Font_GetEncodingName(font)
+        };
+
+        if name.is_null() {
+            return Ok("");
+        }
+
+        Ok(unsafe { CStr::from_ptr(name) }.to_str()?)
+    }
+
+    /// Convert the text into the encoding of the page's current font.
+    ///
+    /// With the `encoding` feature, texts for the CJK multi-byte encoders are converted and
+    /// characters which cannot be represented result in `EncodingError`. Other texts are passed as UTF-8.
+    pub(crate) fn encode_text<'t>(&self, text: &'t str) -> anyhow::Result<Cow<'t, [u8]>> {
+        Ok(crate::encoder::encode_str(self.current_encoding_name()?, text)?)
+    }
+
     /// Gets the size of the page's current font.
     pub fn current_font_size(&self) -> anyhow::Result<Real> {
         let ret = unsafe {
@@ -241,7 +660,7 @@ impl<'a> Page<'a> {
 
     /// Get the width of the text in current fontsize, character spacing and word spacing.
     pub fn text_width(&self, txt: &str) -> anyhow::Result<Real> {
-        let txt = CString::new(txt)?;
+        let txt = CString::new(self.encode_text(txt)?)?;
         let ret = unsafe {
             libharu_sys::HPDF_Page_TextWidth(self.handle(), std::mem::transmute(txt.as_ptr()))
         };
@@ -251,8 +670,8 @@ impl<'a> Page<'a> {
 
     /// Calculate the byte length which can be included within the specified width.
     pub fn measure_text(&self, text: &str, width: Real, wordwrap: bool) -> anyhow::Result<(usize, Real)> {
-        let orig_text = text.clone();
-        let text = CString::new(text)?;
+        let orig_text = text;
+        let text = CString::new(self.encode_text(text)?)?;
         let wordwrap = match wordwrap {
             true => 1,
             false => 0,
@@ -264,21 +683,7 @@ impl<'a> Page<'a> {
         };
 
         /* calc UTF8 boundary */
-        let ret = ret as usize;
-        let ret = if !orig_text.is_char_boundary(ret) {
-            let mut new_ret = 0;
-            for i in 1..ret {
-                if orig_text.is_char_boundary(ret-i) {
-                    new_ret = ret - i;
-                    break;
-                }
-            }
-
-            new_ret
-        }
-        else {
-            ret
-        };
+        let ret = crate::encoder::source_len(self.current_encoding_name()?, orig_text, ret as usize);
 
         Ok((ret as usize, real_width))
     }
@@ -342,7 +747,7 @@ impl<'a> Page<'a> {
 
 
     /// Create a new destination object for the page.
-    pub fn create_destination(&self) -> anyhow::Result<Destination> {
+    pub fn create_destination(&self) -> anyhow::Result<Destination<'_, 'a>> {
         let dst = unsafe {
             libharu_sys::HPDF_Page_CreateDestination(self.handle())
         };
@@ -353,7 +758,290 @@ impl<'a> Page<'a> {
 
         Ok(Destination::new(self, dst))
     }
-    
+
+    /// Create a new link annotation object for the page.
+    pub fn create_link_annot<T>(&self, rect: T, dst: &Destination<'_, '_>) -> anyhow::Result<LinkAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateLinkAnnot(self.handle(), hpdf_rect(rect.into()), dst.handle())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateLinkAnnot failed");
+        }
+
+        Ok(LinkAnnotation::new(self, annot))
+    }
+
+    /// Create a new link annotation which jumps to the named destination `name` of another PDF file.
+    ///
+    /// `file` is a path relative to the document, with `/` as the separator.
+    pub fn create_remote_link_annot<T>(&self, rect: T, file: &str, name: &str) -> anyhow::Result<LinkAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        crate::destination::validate_name(name)?;
+        if file.is_empty() {
+            anyhow::bail!("file name is empty");
+        }
+        let file = CString::new(file)?;
+
+        /* libharu has no GoToR action, so rewrite the action of an URI link */
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateURILinkAnnot(self.handle(), hpdf_rect(rect.into()), file.as_ptr())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateURILinkAnnot failed");
+        }
+
+        unsafe {
+            let mmgr = crate::ffi::dict_mmgr(annot);
+            let action = crate::ffi::HPDF_Dict_GetItem(annot, CString::new("A")?.as_ptr(), crate::ffi::HPDF_OCLASS_DICT);
+            let file_name = crate::ffi::HPDF_String_New(mmgr, file.as_ptr(), std::ptr::null_mut());
+            if action.is_null() || file_name.is_null() {
+                anyhow::bail!("HPDF_Dict_GetItem failed");
+            }
+
+            crate::ffi::check(crate::ffi::HPDF_Dict_RemoveElement(action, CString::new("URI")?.as_ptr()), "HPDF_Dict_RemoveElement")?;
+            crate::ffi::check(crate::ffi::HPDF_Dict_AddName(action, CString::new("S")?.as_ptr(), CString::new("GoToR")?.as_ptr()), "HPDF_Dict_AddName")?;
+            crate::ffi::check(crate::ffi::HPDF_Dict_Add(action, CString::new("F")?.as_ptr(), file_name), "HPDF_Dict_Add")?;
+            crate::ffi::check(crate::ffi::HPDF_Dict_Add(action, CString::new("D")?.as_ptr(), crate::destination::name_string_new(mmgr, name)?), "HPDF_Dict_Add")?;
+        }
+
+        Ok(LinkAnnotation::new(self, annot))
+    }
+
+    /// Create a new text markup annotation which marks the areas of `quads`.
+    pub fn create_text_markup_annot(&self, kind: TextMarkupKind, quads: &[Quad], contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>> {
+        let subtype = match kind {
+            TextMarkupKind::Highlight => libharu_sys::HPDF_AnnotType::HPDF_ANNOT_HIGHTLIGHT,
+            TextMarkupKind::Underline => libharu_sys::HPDF_AnnotType::HPDF_ANNOT_UNDERLINE,
+            TextMarkupKind::Squiggly => libharu_sys::HPDF_AnnotType::HPDF_ANNOT_SQUIGGLY,
+            TextMarkupKind::StrikeOut => libharu_sys::HPDF_AnnotType::HPDF_ANNOT_STRIKE_OUT,
+        };
+        let text = CString::new("")?;
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateTextMarkupAnnot(self.handle(), hpdf_rect(Rect::from((0.0, 0.0, 0.0, 0.0))), text.as_ptr(), std::ptr::null_mut(), subtype)
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateTextMarkupAnnot failed");
+        }
+
+        let annot = TextMarkupAnnotation::new(self, annot);
+        annot.set_quad_points(quads)?;
+        annot.set_contents(contents)?;
+
+        Ok(annot)
+    }
+
+    /// Create a new highlight annotation for the page.
+    pub fn create_highlight_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        self.create_text_markup_annot(TextMarkupKind::Highlight, &[rect.into().into()], contents)
+    }
+
+    /// Create a new underline annotation for the page.
+    pub fn create_underline_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        self.create_text_markup_annot(TextMarkupKind::Underline, &[rect.into().into()], contents)
+    }
+
+    /// Create a new squiggly underline annotation for the page.
+    pub fn create_squiggly_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        self.create_text_markup_annot(TextMarkupKind::Squiggly, &[rect.into().into()], contents)
+    }
+
+    /// Create a new strike-out annotation for the page.
+    pub fn create_strike_out_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<TextMarkupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        self.create_text_markup_annot(TextMarkupKind::StrikeOut, &[rect.into().into()], contents)
+    }
+
+    /// Create a new free text annotation, which displays the text directly on the page.
+    pub fn create_free_text_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<FreeTextAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let text = CString::new("")?;
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateFreeTextAnnot(self.handle(), hpdf_rect(rect.into()), text.as_ptr(), std::ptr::null_mut())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateFreeTextAnnot failed");
+        }
+
+        let annot = FreeTextAnnotation::new(self, annot);
+        annot.set_contents(contents)?;
+
+        Ok(annot)
+    }
+
+    /// Create a new line annotation from `start` to `end`.
+    pub fn create_line_annot<T1, T2>(&self, start: T1, end: T2, contents: &str) -> anyhow::Result<LineAnnotation<'_, '_>>
+    where
+        T1: Into<Point>,
+        T2: Into<Point>,
+    {
+        let text = CString::new("")?;
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateLineAnnot(self.handle(), text.as_ptr(), std::ptr::null_mut())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateLineAnnot failed");
+        }
+
+        let annot = LineAnnotation::new(self, annot);
+        annot.set_position(start, LineEnding::None, end, LineEnding::None)?;
+        annot.set_contents(contents)?;
+
+        Ok(annot)
+    }
+
+    /// Create a new square annotation, which displays a rectangle on the page.
+    pub fn create_square_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<ShapeAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let text = CString::new("")?;
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateSquareAnnot(self.handle(), hpdf_rect(rect.into()), text.as_ptr(), std::ptr::null_mut())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateSquareAnnot failed");
+        }
+
+        let annot = ShapeAnnotation::new(self, annot);
+        annot.set_contents(contents)?;
+
+        Ok(annot)
+    }
+
+    /// Create a new circle annotation, which displays an ellipse inscribed in the rect.
+    pub fn create_circle_annot<T>(&self, rect: T, contents: &str) -> anyhow::Result<ShapeAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let text = CString::new("")?;
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateCircleAnnot(self.handle(), hpdf_rect(rect.into()), text.as_ptr(), std::ptr::null_mut())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateCircleAnnot failed");
+        }
+
+        let annot = ShapeAnnotation::new(self, annot);
+        annot.set_contents(contents)?;
+
+        Ok(annot)
+    }
+
+    /// Create a new stamp annotation with a predefined icon.
+    pub fn create_stamp_annot<T>(&self, rect: T, name: StampName, contents: &str) -> anyhow::Result<StampAnnotation<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        let name = match name {
+            StampName::Approved => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_APPROVED,
+            StampName::Experimental => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_EXPERIMENTAL,
+            StampName::NotApproved => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_NOTAPPROVED,
+            StampName::AsIs => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_ASIS,
+            StampName::Expired => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_EXPIRED,
+            StampName::NotForPublicRelease => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_NOTFORPUBLICRELEASE,
+            StampName::Confidential => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_CONFIDENTIAL,
+            StampName::Final => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_FINAL,
+            StampName::Sold => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_SOLD,
+            StampName::Departmental => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_DEPARTMENTAL,
+            StampName::ForComment => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_FORCOMMENT,
+            StampName::TopSecret => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_TOPSECRET,
+            StampName::Draft => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_DRAFT,
+            StampName::ForPublicRelease => libharu_sys::HPDF_StampAnnotName::HPDF_STAMP_ANNOT_FORPUBLICRELEASE,
+        };
+        let text = CString::new("")?;
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreateStampAnnot(self.handle(), hpdf_rect(rect.into()), name, text.as_ptr(), std::ptr::null_mut())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreateStampAnnot failed");
+        }
+
+        let annot = StampAnnotation::new(self, annot);
+        annot.set_contents(contents)?;
+
+        Ok(annot)
+    }
+
+    /// Create a new popup annotation, which displays the comment of `parent` in the rect.
+    pub fn create_popup_annot<T, A>(&self, rect: T, parent: &A) -> anyhow::Result<PopupAnnotation<'_, '_>>
+    where
+        T: Into<Rect>,
+        A: MarkupAnnotation,
+    {
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_CreatePopupAnnot(self.handle(), hpdf_rect(rect.into()), parent.handle())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_CreatePopupAnnot failed");
+        }
+
+        let status = unsafe {
+            libharu_sys::HPDF_MarkupAnnot_SetPopup(parent.handle(), annot)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_MarkupAnnot_SetPopup failed (status={})", status);
+        }
+
+        Ok(PopupAnnotation::new(self, annot))
+    }
+
+    /// Create a new 3D annotation which shows the 3D artwork in `rect`.
+    ///
+    /// The artwork is activated when the page is opened, with the toolbar of the viewer.
+    /// PDF/A-1 does not allow 3D annotations.
+    pub fn create_3d_annot<T>(&self, rect: T, u3d: &U3D<'_>) -> anyhow::Result<Annotation3D<'_, '_>>
+    where
+        T: Into<Rect>
+    {
+        if self.doc.conformance() == Conformance::PdfA1B {
+            return Err(ConformanceError::Artwork3D.into());
+        }
+
+        let annot = unsafe {
+            libharu_sys::HPDF_Page_Create3DAnnot(self.handle(), hpdf_rect(rect.into()), 1, 0, u3d.handle(), std::ptr::null_mut())
+        };
+
+        if annot.is_null() {
+            anyhow::bail!("HPDF_Page_Create3DAnnot failed");
+        }
+
+        Ok(Annotation3D::new(self, annot))
+    }
+
     /// Get the current position for path painting.
     pub fn current_pos(&self) -> anyhow::Result<Point> {
         let point = unsafe {
@@ -364,22 +1052,30 @@ impl<'a> Page<'a> {
     }
 
     /// Set the size and direction of a page to a predefined size.
+    ///
+    /// The direction applies only to the named sizes; `PageSize::Custom` is used as given.
     pub fn set_size(&self, size: PageSize, direction: PageDirection) -> anyhow::Result<()> {
-        let size = match size {
-            PageSize::Letter => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_LETTER,
-            PageSize::Legal => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_LEGAL,
-            PageSize::A3 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_A3,
-            PageSize::A4 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_A4,
-            PageSize::A5 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_A5,
-            PageSize::B4 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_B4,
-            PageSize::B5 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_B5,
-            PageSize::Executive => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_EXECUTIVE,
-            PageSize::US4x6 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_US4x6,
-            PageSize::US4x8 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_US4x8,
-            PageSize::US5x7 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_US5x7,
-            PageSize::Comm10 => libharu_sys::HPDF_PageSizes::HPDF_PAGE_SIZE_COMM10,
-        };
-        
+        let size = match size.preset() {
+            Some(size) => size,
+            None => {
+                /* named sizes libharu does not know are set by their dimensions */
+                let (w, h) = size.dimensions();
+                let (width, height) = match (size, direction) {
+                    (PageSize::Custom { .. }, _) | (_, PageDirection::Portrait) => (w, h),
+                    (_, PageDirection::Landscape) => (h, w),
+                };
+
+                /* check both sides first, so that an invalid size does not change only the width */
+                let valid = |v: Length| (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&v.points());
+                if !valid(width) || !valid(height) {
+                    return Err(Error::PageInvalidSize.into());
+                }
+
+                self.set_width(width.points())?;
+                return self.set_height(height.points());
+            }
+        };
+
         let direction = match direction {
             PageDirection::Portrait => libharu_sys::HPDF_PageDirection::HPDF_PAGE_PORTRAIT,
             PageDirection::Landscape => libharu_sys::HPDF_PageDirection::HPDF_PAGE_LANDSCAPE,
@@ -396,6 +1092,24 @@ impl<'a> Page<'a> {
         Ok(())
     }
 
+    /// Get the size of the page, as a named size if it matches one, and its direction.
+    ///
+    /// Sizes within 1 point of a named size match it. Otherwise `PageSize::Custom` is returned.
+    pub fn size(&self) -> anyhow::Result<(PageSize, PageDirection)> {
+        let (width, height) = (self.width()?, self.height()?);
+        let direction = if width > height { PageDirection::Landscape } else { PageDirection::Portrait };
+        let (short, long) = (width.min(height), width.max(height));
+
+        let size = PAGE_SIZE_PRESETS.iter().copied()
+            .find(|size| {
+                let (w, h) = size.dimensions();
+                (w.points() - short).abs() < 1.0 && (h.points() - long).abs() < 1.0
+            })
+            .unwrap_or(PageSize::Custom { width: Length::pt(width), height: Length::pt(height) });
+
+        Ok((size, direction))
+    }
+
     /// Set rotation angle of the page.
     pub fn set_rotate(&self, angle: u16) -> anyhow::Result<()> {
         let status = unsafe {
@@ -409,6 +1123,206 @@ impl<'a> Page<'a> {
         Ok(())
     }
 
+    /// Get the media box of the page, which is controlled by `set_size`, `set_width` and `set_height`.
+    pub fn media_box(&self) -> anyhow::Result<Rect> {
+        /* libharu keeps the lower left corner of the media box at the origin */
+        Ok(Rect { left: 0.0, bottom: 0.0, right: self.width()?, top: self.height()? })
+    }
+
+    /// Get the box of the page.
+    ///
+    /// If the box is not set, the default is returned: the media box for the crop box, and the crop box for the others.
+    /// A box set before the media box was shrunk is clipped to the current media box.
+    ///
+    /// Reading a box set by `set_box` requires the `bundled-internals` feature.
+    pub fn page_box(&self, box_type: BoxType) -> anyhow::Result<Rect> {
+        let array = unsafe {
+            crate::ffi::HPDF_Dict_GetItem(self.handle(), CString::new(box_type.key())?.as_ptr(), crate::ffi::HPDF_OCLASS_ARRAY)
+        };
+
+        if array.is_null() {
+            return match box_type {
+                BoxType::Crop => self.media_box(),
+                _ => self.page_box(BoxType::Crop),
+            };
+        }
+
+        let mut values = [0.0; 4];
+        for (index, value) in values.iter_mut().enumerate() {
+            unsafe {
+                let real = crate::ffi::HPDF_Array_GetItem(array, index as u32, crate::ffi::HPDF_OCLASS_REAL);
+                if real.is_null() {
+                    anyhow::bail!("HPDF_Array_GetItem failed");
+                }
+                *value = crate::ffi::real_value(real)?;
+            }
+        }
+
+        /* viewers use the intersection with the media box, which can shrink after the box is set */
+        let media = self.media_box()?;
+        let rect = Rect {
+            left: values[0].max(media.left),
+            bottom: values[1].max(media.bottom),
+            right: values[2].min(media.right),
+            top: values[3].min(media.top),
+        };
+        if rect.left >= rect.right || rect.bottom >= rect.top {
+            anyhow::bail!("{} is out of the media box", box_type.key());
+        }
+
+        Ok(rect)
+    }
+
+    /// Set the box of the page. The box must be inside the media box.
+    pub fn set_box<T>(&self, box_type: BoxType, rect: T) -> anyhow::Result<()>
+    where
+        T: Into<Rect>
+    {
+        let rect = rect.into();
+        let media = self.media_box()?;
+        if rect.left >= rect.right || rect.bottom >= rect.top {
+            anyhow::bail!("box is empty ({:?})", rect);
+        }
+        if rect.left < media.left || rect.bottom < media.bottom || rect.right > media.right || rect.top > media.top {
+            anyhow::bail!("box is out of the media box ({:?})", rect);
+        }
+
+        unsafe {
+            let array = crate::ffi::HPDF_Array_New(crate::ffi::dict_mmgr(self.handle()));
+            if array.is_null() {
+                anyhow::bail!("HPDF_Array_New failed");
+            }
+
+            for value in [rect.left, rect.bottom, rect.right, rect.top] {
+                crate::ffi::check(crate::ffi::HPDF_Array_AddReal(array, value), "HPDF_Array_AddReal")?;
+            }
+            crate::ffi::check(crate::ffi::HPDF_Dict_Add(self.handle(), CString::new(box_type.key())?.as_ptr(), array), "HPDF_Dict_Add")?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the trim box to `trim` and the bleed box to `trim` extended by `bleed` on each side,
+    /// and draw crop marks at the corners of the trim box outside the bleed box.
+    ///
+    /// The crop box must leave room for the marks around the bleed box. The marks are drawn in
+    /// the registration color (100% of every CMYK ink), and are shortened if the room is narrow.
+    /// PDF/A-1 documents have an RGB output intent, so the marks are drawn in DeviceGray black instead.
+    pub fn set_bleed_with_crop_marks<T>(&self, trim: T, bleed: Real) -> anyhow::Result<()>
+    where
+        T: Into<Rect>
+    {
+        let trim = trim.into();
+        if bleed < 0.0 {
+            anyhow::bail!("bleed out of range ({})", bleed);
+        }
+
+        let outer = Rect {
+            left: trim.left - bleed,
+            top: trim.top + bleed,
+            right: trim.right + bleed,
+            bottom: trim.bottom - bleed,
+        };
+
+        let crop = self.page_box(BoxType::Crop)?;
+        let length = [outer.left - crop.left, crop.right - outer.right, outer.bottom - crop.bottom, crop.top - outer.top]
+            .iter()
+            .fold(CROP_MARK_LENGTH, |length, room| length.min(*room));
+        if length <= 0.0 {
+            anyhow::bail!("no room for crop marks around the bleed box");
+        }
+
+        self.set_box(BoxType::Trim, trim)?;
+        self.set_box(BoxType::Bleed, outer)?;
+
+        let page = PageDescriptionMode::new(self);
+        page.gsave()?;
+        page.set_line_width(CROP_MARK_WIDTH)?;
+        if self.doc.conformance() == Conformance::PdfA1B {
+            page.set_gray_stroke(0.0)?;
+        } else {
+            page.set_cmyk_stroke((1.0, 1.0, 1.0, 1.0))?;
+        }
+        page.run_path_mode(|page| {
+            for x in &[trim.left, trim.right] {
+                page.move_to((*x, outer.top))?;
+                page.line_to((*x, outer.top + length))?;
+                page.move_to((*x, outer.bottom))?;
+                page.line_to((*x, outer.bottom - length))?;
+            }
+            for y in &[trim.bottom, trim.top] {
+                page.move_to((outer.left, *y))?;
+                page.line_to((outer.left - length, *y))?;
+                page.move_to((outer.right, *y))?;
+                page.line_to((outer.right + length, *y))?;
+            }
+            page.stroke()
+        })?;
+        page.grestore()
+    }
+
+    /// Set the magnification (0.08 ~ 32) which the viewer uses for the page.
+    pub fn set_zoom(&self, zoom: Real) -> anyhow::Result<()> {
+        if !(0.08..=32.0).contains(&zoom) {
+            anyhow::bail!("zoom out of range ({})", zoom);
+        }
+
+        let status = unsafe {
+            libharu_sys::HPDF_Page_SetZoom(self.handle(), zoom)
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Page_SetZoom failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Configure the setting for slide transition of the page.
+    ///
+    /// `display_time` is the display duration of the page in seconds, and `transition_time` is
+    /// the duration of the transition effect in seconds. Negative durations are rejected before
+    /// calling libharu, and the returned error can be downcast to [`Error`].
+    pub fn set_slide_show(&self, transition: Transition, display_time: Real, transition_time: Real) -> anyhow::Result<()> {
+        /* libharu would raise the error to the document */
+        if display_time < 0.0 || display_time.is_nan() {
+            return Err(Error::PageInvalidDisplayTime.into());
+        }
+        if transition_time < 0.0 || transition_time.is_nan() {
+            return Err(Error::PageInvalidTransitionTime.into());
+        }
+
+        let transition = match transition {
+            Transition::WipeRight => libharu_sys::HPDF_TransitionStyle::HPDF_TS_WIPE_RIGHT,
+            Transition::WipeUp => libharu_sys::HPDF_TransitionStyle::HPDF_TS_WIPE_UP,
+            Transition::WipeLeft => libharu_sys::HPDF_TransitionStyle::HPDF_TS_WIPE_LEFT,
+            Transition::WipeDown => libharu_sys::HPDF_TransitionStyle::HPDF_TS_WIPE_DOWN,
+            Transition::BarnDoorsHorizontalOut => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BARN_DOORS_HORIZONTAL_OUT,
+            Transition::BarnDoorsHorizontalIn => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BARN_DOORS_HORIZONTAL_IN,
+            Transition::BarnDoorsVerticalOut => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BARN_DOORS_VERTICAL_OUT,
+            Transition::BarnDoorsVerticalIn => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BARN_DOORS_VERTICAL_IN,
+            Transition::BoxOut => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BOX_OUT,
+            Transition::BoxIn => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BOX_IN,
+            Transition::BlindsHorizontal => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BLINDS_HORIZONTAL,
+            Transition::BlindsVertical => libharu_sys::HPDF_TransitionStyle::HPDF_TS_BLINDS_VERTICAL,
+            Transition::Dissolve => libharu_sys::HPDF_TransitionStyle::HPDF_TS_DISSOLVE,
+            Transition::GlitterRight => libharu_sys::HPDF_TransitionStyle::HPDF_TS_GLITTER_RIGHT,
+            Transition::GlitterDown => libharu_sys::HPDF_TransitionStyle::HPDF_TS_GLITTER_DOWN,
+            Transition::GlitterTopLeftToBottomRight => libharu_sys::HPDF_TransitionStyle::HPDF_TS_GLITTER_TOP_LEFT_TO_BOTTOM_RIGHT,
+            Transition::Replace => libharu_sys::HPDF_TransitionStyle::HPDF_TS_REPLACE,
+        };
+
+        let status = unsafe {
+            libharu_sys::HPDF_Page_SetSlideShow(self.handle(), transition, display_time, transition_time)
+        };
+
+        if status != 0 {
+            return Err(Error::from_num(status).into());
+        }
+
+        Ok(())
+    }
+
     /// Show an image in one operation.
     pub fn draw_image<T>(&self, img: &Image, pos: T, width: Real, height: Real) -> anyhow::Result<()>
     where
@@ -431,4 +1345,229 @@ impl<'a> Page<'a> {
 
         Ok(())
     }
+
+    /// Draw the XObject using the current transformation matrix.
+    pub fn execute_xobject(&self, xobj: &XObject) -> anyhow::Result<()> {
+        let status = unsafe {
+            crate::ffi::HPDF_Page_ExecuteXObject(self.handle(), xobj.handle())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Page_ExecuteXObject failed (status={})", status);
+        }
+
+        Ok(())
+    }
+
+    /// Draw the XObject transformed by the matrix. The graphics state of the page is left unchanged.
+    pub fn execute_xobject_with_matrix<T>(&self, xobj: &XObject, matrix: T) -> anyhow::Result<()>
+    where
+        T: Into<TransMatrix>
+    {
+        self.gsave()?;
+        self.concat(matrix)?;
+        self.execute_xobject(xobj)?;
+        self.grestore()?;
+
+        Ok(())
+    }
+
+    /// Select the ICC based color space and set the filling or stroking color.
+    pub(crate) fn set_icc_color(&self, profile: &IccProfile, components: &[Real], stroke: bool) -> anyhow::Result<()> {
+        if components.len() != profile.components() {
+            anyhow::bail!("ICC color has {} components, expected {}", components.len(), profile.components());
+        }
+
+        if components.iter().any(|v| !(0.0..=1.0).contains(v)) {
+            anyhow::bail!("ICC color components must be in range 0.0 to 1.0");
+        }
+
+        let name = unsafe {
+            let resources = crate::ffi::HPDF_Page_GetInheritableItem(self.handle(), CString::new("Resources")?.as_ptr(), crate::ffi::HPDF_OCLASS_DICT);
+            if resources.is_null() {
+                anyhow::bail!("page has no resources");
+            }
+
+            let key = CString::new("ColorSpace")?;
+            let mut color_spaces = crate::ffi::HPDF_Dict_GetItem(resources, key.as_ptr(), crate::ffi::HPDF_OCLASS_DICT);
+            if color_spaces.is_null() {
+                color_spaces = crate::ffi::HPDF_Dict_New(crate::ffi::dict_mmgr(self.handle()));
+                if color_spaces.is_null() || crate::ffi::HPDF_Dict_Add(resources, key.as_ptr(), color_spaces) != 0 {
+                    anyhow::bail!("failed to create ColorSpace resource");
+                }
+            }
+
+            let mut name = crate::ffi::HPDF_Dict_GetKeyByObj(color_spaces, profile.handle());
+            if name.is_null() {
+                /* register the color space with the first unused name */
+                let mut index = 1;
+                let new_name = loop {
+                    let new_name = CString::new(format!("CS{}", index))?;
+                    if crate::ffi::HPDF_Dict_GetItem(color_spaces, new_name.as_ptr(), crate::ffi::HPDF_OCLASS_ARRAY).is_null() {
+                        break new_name;
+                    }
+                    index += 1;
+                };
+
+                if crate::ffi::HPDF_Dict_Add(color_spaces, new_name.as_ptr(), profile.handle()) != 0 {
+                    anyhow::bail!("failed to register ICC color space");
+                }
+                name = crate::ffi::HPDF_Dict_GetKeyByObj(color_spaces, profile.handle());
+            }
+
+            std::ffi::CStr::from_ptr(name).to_str()?.to_string()
+        };
+
+        let values: Vec<String> = components.iter().map(|v| v.to_string()).collect();
+        let (cs, sc) = if stroke { ("CS", "SCN") } else { ("cs", "scn") };
+        let ops = CString::new(format!("/{} {}\n{} {}\n", name, cs, values.join(" "), sc))?;
+
+        let status = unsafe {
+            crate::ffi::HPDF_Stream_WriteStr(crate::ffi::page_stream(self.handle())?, ops.as_ptr())
+        };
+
+        if status != 0 {
+            anyhow::bail!("HPDF_Stream_WriteStr failed (status={})", status);
+        }
+
+        /* keep the graphics state in sync, so that the color getters do not report the previous color */
+        unsafe {
+            crate::ffi::set_color_space(self.handle(), libharu_sys::HPDF_ColorSpace::HPDF_CS_ICC_BASED, stroke)
+        }
+    }
+}
+
+fn hpdf_rect(rect: Rect) -> libharu_sys::HPDF_Rect {
+    libharu_sys::HPDF_Rect {
+        left: rect.left,
+        bottom: rect.bottom,
+        right: rect.right,
+        top: rect.top,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_round_trips_presets() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let page = doc.add_page().unwrap();
+
+        for size in PAGE_SIZE_PRESETS.iter().copied() {
+            for direction in [PageDirection::Portrait, PageDirection::Landscape] {
+                page.set_size(size, direction).unwrap();
+                assert_eq!(page.size().unwrap(), (size, direction));
+            }
+        }
+    }
+
+    #[test]
+    fn page_size_presets_do_not_collide() {
+        for (i, a) in PAGE_SIZE_PRESETS.iter().enumerate() {
+            for b in &PAGE_SIZE_PRESETS[i + 1..] {
+                let ((aw, ah), (bw, bh)) = (a.dimensions(), b.dimensions());
+                /* `Page::size` matches sizes within 1 point */
+                assert!((aw.points() - bw.points()).abs() >= 1.0 || (ah.points() - bh.points()).abs() >= 1.0, "{:?} and {:?} collide", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn custom_size_is_validated_before_setting() {
+        let doc = Document::new(|_| {}).unwrap();
+        let page = doc.add_page().unwrap();
+        page.set_size(PageSize::A4, PageDirection::Portrait).unwrap();
+
+        for (width, height) in [(100.0, 20000.0), (2.0, 100.0), (14400.0, 14401.0)] {
+            let size = PageSize::Custom { width: Length::pt(width), height: Length::pt(height) };
+            let err = page.set_size(size, PageDirection::Portrait).unwrap_err();
+            assert!(matches!(err.downcast_ref::<Error>(), Some(Error::PageInvalidSize)));
+            assert_eq!(page.size().unwrap(), (PageSize::A4, PageDirection::Portrait));
+        }
+
+        let size = PageSize::Custom { width: Length::pt(3.0), height: Length::pt(14400.0) };
+        page.set_size(size, PageDirection::Portrait).unwrap();
+        assert_eq!((page.width().unwrap(), page.height().unwrap()), (3.0, 14400.0));
+    }
+
+    #[test]
+    fn custom_size_ignores_direction() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let page = doc.add_page().unwrap();
+
+        let size = PageSize::Custom { width: Length::pt(500.0), height: Length::pt(300.0) };
+        for direction in [PageDirection::Portrait, PageDirection::Landscape] {
+            page.set_size(size, direction).unwrap();
+            assert_eq!((page.width().unwrap(), page.height().unwrap()), (500.0, 300.0));
+            assert_eq!(page.size().unwrap(), (size, PageDirection::Landscape));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-internals")]
+    fn boxes_are_read_from_page_dictionary() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let page = doc.add_page().unwrap();
+        page.set_width(600.0).unwrap();
+        page.set_height(800.0).unwrap();
+
+        assert_eq!(page.page_box(BoxType::Trim).unwrap(), Rect { left: 0.0, bottom: 0.0, right: 600.0, top: 800.0 });
+
+        page.set_box(BoxType::Crop, Rect { left: 10.0, bottom: 10.0, right: 590.0, top: 790.0 }).unwrap();
+        page.set_box(BoxType::Trim, Rect { left: 50.0, bottom: 50.0, right: 550.0, top: 750.0 }).unwrap();
+        assert_eq!(page.page_box(BoxType::Art).unwrap(), Rect { left: 10.0, bottom: 10.0, right: 590.0, top: 790.0 });
+        assert_eq!(page.page_box(BoxType::Trim).unwrap(), Rect { left: 50.0, bottom: 50.0, right: 550.0, top: 750.0 });
+
+        /* the boxes of other pages are independent */
+        let other = doc.add_page().unwrap();
+        assert_eq!(other.page_box(BoxType::Trim).unwrap(), other.media_box().unwrap());
+
+        /* shrinking the media box clips the boxes set before */
+        page.set_height(600.0).unwrap();
+        assert_eq!(page.page_box(BoxType::Trim).unwrap(), Rect { left: 50.0, bottom: 50.0, right: 550.0, top: 600.0 });
+        page.set_height(40.0).unwrap();
+        assert!(page.page_box(BoxType::Trim).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-internals")]
+    fn crop_marks_avoid_cmyk_in_pdfa() {
+        for (conformance, operator) in [(Conformance::None, &b"1 1 1 1 K"[..]), (Conformance::PdfA1B, &b"0 G"[..])] {
+            let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+            doc.set_conformance(conformance).unwrap();
+            let page = doc.add_page().unwrap();
+            page.set_bleed_with_crop_marks(Rect { left: 100.0, bottom: 100.0, right: 400.0, top: 600.0 }, 9.0).unwrap();
+
+            let bytes = doc.save_to_bytes().unwrap();
+            let has = |operator: &[u8]| bytes.windows(operator.len()).any(|w| w == operator);
+            assert!(has(operator), "{:?}", conformance);
+            assert_eq!(has(b"1 1 1 1 K"), conformance == Conformance::None);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bundled-internals")]
+    fn icc_color_updates_graphics_state() {
+        let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+        let profile = doc.load_srgb_icc_profile().unwrap();
+        let raw = doc.add_page().unwrap();
+        let page = PageDescriptionMode::new(&raw);
+
+        page.set_text_leading(12.5).unwrap();
+        page.set_rgb_fill((1.0, 0.0, 0.0)).unwrap();
+        page.set_icc_fill(&profile, &[0.0, 1.0, 0.0]).unwrap();
+
+        let (fill, stroke) = unsafe {
+            (libharu_sys::HPDF_Page_GetFillingColorSpace(raw.handle()), libharu_sys::HPDF_Page_GetStrokingColorSpace(raw.handle()))
+        };
+        assert_eq!(page.text_leading().unwrap(), 12.5);
+        assert!(matches!(fill, libharu_sys::HPDF_ColorSpace::HPDF_CS_ICC_BASED));
+        assert!(matches!(stroke, libharu_sys::HPDF_ColorSpace::HPDF_CS_DEVICE_GRAY));
+
+        /* the RGB color is no longer current */
+        let color = page.rgb_fill().unwrap();
+        assert_eq!((color.red, color.green, color.blue), (0.0, 0.0, 0.0));
+    }
 }
@@ -1,9 +1,13 @@
 
 pub use crate::document::{
     Document,
+    DocumentBuilder,
+    PageIter,
     PageNumStyle,
     CompressionMode,
     PageMode,
+    PageLayout,
+    ViewerPreferences,
 };
 
 pub use crate::page::{
@@ -13,11 +17,18 @@ pub use crate::page::{
     TextRenderingMode,
     PageSize,
     PageDirection,
+    BoxType,
     TextAlignment,
+    Transition,
 };
 
 pub use crate::outline::{
     Outline,
+    OutlineStyle,
+    OutlineNode,
+    OutlineTree,
+    OutlineTitle,
+    OutlineTreeIter,
 };
 
 pub use crate::destination::{
@@ -28,12 +39,93 @@ pub use crate::image::{
     Image,
 };
 
+pub use crate::annotation::{
+    Annotation,
+    MarkupAnnotation,
+    LinkAnnotation,
+    TextMarkupAnnotation,
+    FreeTextAnnotation,
+    LineAnnotation,
+    ShapeAnnotation,
+    StampAnnotation,
+    PopupAnnotation,
+    Annotation3D,
+    HighlightMode,
+    TextMarkupKind,
+    StampName,
+    LineEnding,
+    Quad,
+};
+
+pub use crate::u3d::{
+    U3D,
+    View3D,
+    Camera3D,
+    Projection3D,
+    Lighting3D,
+};
+
+pub use crate::encoder::{
+    Encoder,
+    EncoderType,
+    ByteType,
+    WritingMode,
+    DecodeIter,
+    EncodingError,
+};
+
+pub use crate::xobject::{
+    XObject,
+};
+
+pub use crate::embedded_file::{
+    EmbeddedFile,
+};
+
+pub use crate::info::{
+    InfoType,
+    InfoDate,
+};
+
+pub use crate::conformance::{
+    Conformance,
+    ConformanceError,
+};
+
+pub use crate::memory::{
+    MemoryStats,
+};
+
+pub use crate::icc::{
+    IccProfile,
+    OutputIntent,
+    OutputIntentSubtype,
+};
+
+pub use crate::xmp::{
+    XmpMetadata,
+};
+
+pub use crate::toc::{
+    TableOfContents,
+};
+
+pub use crate::vertical::{
+    VerticalText,
+};
+
 pub use crate::context::{
     PageTextMode,
     PagePathMode,
     PageDescriptionMode,
     PageDescTeextCommonFunction,
     PageDescPathCommonFunction,
+    Origin,
+};
+
+pub use crate::error::{
+    Error,
+    ErrorDetail,
 };
 
 pub use crate::{
@@ -42,5 +134,7 @@ pub use crate::{
     CmykColor,
     Point,
     Rect,
+    Length,
+    TransMatrix,
     Font,
 };
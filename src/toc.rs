@@ -0,0 +1,189 @@
+use crate::prelude::*;
+
+use std::collections::HashMap;
+
+struct TocEntry<'doc> {
+    level: usize,
+    title: String,
+    page: Page<'doc>,
+    dst: Destination<'doc, 'doc>,
+}
+
+/// Table of contents builder.
+///
+/// Headings are collected while the document is laid out. [`TableOfContents::finish`] creates
+/// the outline tree and inserts the table of contents pages at the front of the document.
+pub struct TableOfContents<'doc> {
+    doc: &'doc Document,
+    font: Font<'doc>,
+    font_size: Real,
+    title: String,
+    margin: Real,
+    entries: Vec<TocEntry<'doc>>,
+}
+
+impl<'doc> TableOfContents<'doc> {
+    /// Create a new table of contents printed with the specified font and size.
+    pub fn new(doc: &'doc Document, font: &Font<'doc>, font_size: Real) -> Self {
+        Self {
+            doc,
+            font: font.clone(),
+            font_size,
+            title: String::from("Contents"),
+            margin: 50.0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set the title printed on the top of the table of contents. (default: "Contents")
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    /// Set the margin of the table of contents pages. (default: 50.0)
+    pub fn set_margin(&mut self, margin: Real) {
+        self.margin = margin;
+    }
+
+    /// Add a heading. The level of a top level heading is 0, and a heading can be at most
+    /// one level deeper than the previous one.
+    pub fn add_heading(&mut self, level: usize, title: &str, page: &Page<'doc>, dst: &Destination<'_, 'doc>) -> anyhow::Result<()> {
+        let max_level = match self.entries.last() {
+            Some(entry) => entry.level + 1,
+            None => 0,
+        };
+
+        if level > max_level {
+            anyhow::bail!("heading level {} is too deep (max level={})", level, max_level);
+        }
+
+        self.entries.push(TocEntry {
+            level,
+            title: title.to_string(),
+            page: page.clone(),
+            dst: dst.detached(),
+        });
+
+        Ok(())
+    }
+
+    /// Get the number of collected headings.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return true if no heading is collected.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Create the outline tree and insert the table of contents pages at the front of the document.
+    pub fn finish(self) -> anyhow::Result<()> {
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+
+        self.build_outlines()?;
+        self.render()
+    }
+
+    fn build_outlines(&self) -> anyhow::Result<()> {
+        let mut parents: Vec<Outline> = Vec::new();
+
+        for entry in &self.entries {
+            parents.truncate(entry.level);
+
+            let outline = self.doc.create_outline(&entry.title, parents.last(), None)?;
+            outline.set_destination(&entry.dst)?;
+
+            parents.push(outline);
+        }
+
+        Ok(())
+    }
+
+    fn render(&self) -> anyhow::Result<()> {
+        let first = self.doc.page(0)?;
+        let width = first.width()?;
+        let height = first.height()?;
+
+        let line_height = self.font_size * 1.5;
+        let title_height = self.font_size * 3.0;
+        let lines_per_page = ((height - self.margin * 2.0 - title_height) / line_height).floor();
+        if lines_per_page < 1.0 {
+            anyhow::bail!("page is too small for the table of contents");
+        }
+        let lines_per_page = lines_per_page as usize;
+
+        let page_count = self.entries.len().div_ceil(lines_per_page);
+        let mut toc_pages = Vec::with_capacity(page_count);
+        for _ in 0..page_count {
+            let page = self.doc.insert_page(&first)?;
+            page.set_width(width)?;
+            page.set_height(height)?;
+            toc_pages.push(page);
+        }
+
+        /* page numbers of the headings, after the table of contents pages are inserted */
+        let mut indices = HashMap::with_capacity(self.doc.page_count());
+        for (index, page) in self.doc.pages().enumerate() {
+            indices.insert(page?.handle(), index);
+        }
+
+        for (i, (page, entries)) in toc_pages.iter().zip(self.entries.chunks(lines_per_page)).enumerate() {
+            let page = PageDescriptionMode::new(page);
+
+            if i == 0 {
+                page.set_font_and_size(&self.font, self.font_size * 1.5)?;
+                page.run_text_mode(|page| {
+                    page.text_out((self.margin, height - self.margin - self.font_size * 1.5), &self.title)
+                })?;
+            }
+
+            page.set_font_and_size(&self.font, self.font_size)?;
+            let dot_width = page.text_width(".")?;
+
+            let mut y = height - self.margin - title_height - self.font_size;
+            for entry in entries {
+                let x = self.margin + entry.level as Real * self.font_size * 2.0;
+                let index = match indices.get(&entry.page.handle()) {
+                    Some(index) => *index,
+                    None => anyhow::bail!("page of the heading is not found in the document"),
+                };
+                let num = (index + 1).to_string();
+                let num_x = width - self.margin - page.text_width(&num)?;
+
+                let dots_left = x + page.text_width(&entry.title)? + dot_width;
+                let dots_right = num_x - dot_width;
+                let dots = if dots_right > dots_left {
+                    ((dots_right - dots_left) / dot_width).floor() as usize
+                }
+                else {
+                    0
+                };
+
+                page.run_text_mode(|page| {
+                    page.text_out((x, y), &entry.title)?;
+                    if dots > 0 {
+                        page.text_out((dots_right - dots as Real * dot_width, y), &".".repeat(dots))?;
+                    }
+                    page.text_out((num_x, y), &num)?;
+                    Ok(())
+                })?;
+
+                let rect = Rect {
+                    left: x,
+                    top: y + self.font_size,
+                    right: width - self.margin,
+                    bottom: y - self.font_size * 0.25,
+                };
+                let annot = page.create_link_annot(rect, &entry.dst)?;
+                annot.set_border_style(0.0, 0, 0)?;
+
+                y -= line_height;
+            }
+        }
+
+        Ok(())
+    }
+}
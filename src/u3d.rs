@@ -0,0 +1,252 @@
+//! 3D artwork (U3D and PRC streams) and its views.
+
+use crate::document::Document;
+use crate::error::Error;
+use crate::ffi;
+use crate::{Real, Color};
+
+use std::ffi::CString;
+
+/// 3D artwork handle type.
+pub struct U3D<'a> {
+    doc: &'a Document,
+    u3d: libharu_sys::HPDF_U3D,
+}
+
+impl<'a> U3D<'a> {
+    pub(crate) fn new(doc: &'a Document, u3d: libharu_sys::HPDF_U3D) -> Self {
+        Self { doc, u3d }
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> libharu_sys::HPDF_U3D {
+        self.doc.enter_memory();
+        self.u3d
+    }
+
+    /// Add a view of the artwork, which can be selected in the viewer.
+    pub fn add_view(&self, view: &View3D) -> anyhow::Result<()> {
+        let name = CString::new(view.name.as_str())?;
+
+        let dict = unsafe {
+            ffi::HPDF_Create3DView(ffi::dict_mmgr(self.handle()), name.as_ptr())
+        };
+
+        if dict.is_null() {
+            anyhow::bail!("HPDF_Create3DView failed");
+        }
+
+        /* libharu writes the type with a wrong key */
+        unsafe {
+            ffi::check(ffi::HPDF_Dict_RemoveElement(dict, CString::new("TYPE")?.as_ptr()), "HPDF_Dict_RemoveElement")?;
+            ffi::check(ffi::HPDF_Dict_AddName(dict, CString::new("Type")?.as_ptr(), CString::new("3DView")?.as_ptr()), "HPDF_Dict_AddName")?;
+        }
+
+        view.apply(dict)?;
+
+        let status = unsafe {
+            ffi::HPDF_U3D_Add3DView(self.handle(), dict)
+        };
+
+        if status != 0 {
+            return Err(Error::from_num(status).into());
+        }
+
+        Ok(())
+    }
+
+    /// Add a view of the artwork, and show it when the artwork is activated.
+    pub fn set_default_view(&self, view: &View3D) -> anyhow::Result<()> {
+        self.add_view(view)?;
+
+        let name = CString::new(view.name.as_str())?;
+
+        let status = unsafe {
+            ffi::HPDF_U3D_SetDefault3DView(self.handle(), name.as_ptr())
+        };
+
+        if status != 0 {
+            return Err(Error::from_num(status).into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Lighting scheme of 3D view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lighting3D {
+    /// Lights specified in the artwork.
+    Artwork,
+
+    /// No lights.
+    None,
+
+    /// Three white infinite lights.
+    White,
+
+    /// Three light-grey infinite lights.
+    Day,
+
+    /// One yellow, one aqua, and one blue infinite light.
+    Night,
+
+    /// Three grey infinite lights.
+    Hard,
+
+    /// One red, one green, and one blue infinite light.
+    Primary,
+
+    /// Three blue infinite lights.
+    Blue,
+
+    /// Three red infinite lights.
+    Red,
+
+    /// Six grey infinite lights aligned with the major axes.
+    Cube,
+
+    /// Three grey infinite lights and one light attached to the camera.
+    Cad,
+
+    /// Single infinite light attached to the camera.
+    Headlamp,
+}
+
+impl Lighting3D {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Artwork => "Artwork",
+            Self::None => "None",
+            Self::White => "White",
+            Self::Day => "Day",
+            Self::Night => "Night",
+            Self::Hard => "Hard",
+            Self::Primary => "Primary",
+            Self::Blue => "Blue",
+            Self::Red => "Red",
+            Self::Cube => "Cube",
+            Self::Cad => "CAD",
+            Self::Headlamp => "Headlamp",
+        }
+    }
+}
+
+/// Projection of 3D view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection3D {
+    /// Perspective projection with the field of view in degrees. (0 ~ 180)
+    Perspective(Real),
+
+    /// Orthographic projection with the magnification. (greater than 0)
+    Orthographic(Real),
+}
+
+/// Camera of 3D view, which orbits around a center point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera3D {
+    /// Center of orbit. (x, y, z)
+    pub center: (Real, Real, Real),
+
+    /// Direction from the center of orbit to the camera. (x, y, z)
+    pub direction: (Real, Real, Real),
+
+    /// Distance from the center of orbit to the camera.
+    pub distance: Real,
+
+    /// Roll of the camera in degrees.
+    pub roll: Real,
+}
+
+/// View of 3D artwork.
+#[derive(Debug, Clone)]
+pub struct View3D {
+    /// Name of the view, shown in the viewer.
+    pub name: String,
+
+    /// Camera position. `None` uses the camera of the artwork.
+    pub camera: Option<Camera3D>,
+
+    /// Projection. `None` uses the projection of the artwork.
+    pub projection: Option<Projection3D>,
+
+    /// Lighting scheme. `None` uses the lighting of the viewer.
+    pub lighting: Option<Lighting3D>,
+
+    /// Background color. `None` uses white.
+    pub background: Option<Color>,
+}
+
+impl View3D {
+    /// View with the given name and the settings of the artwork.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            camera: None,
+            projection: None,
+            lighting: None,
+            background: None,
+        }
+    }
+
+    /// Write the settings into a view dictionary.
+    fn apply(&self, dict: libharu_sys::HPDF_Dict) -> anyhow::Result<()> {
+        match self.projection {
+            Some(Projection3D::Perspective(fov)) if !(0.0..=180.0).contains(&fov) => anyhow::bail!("field of view out of range ({})", fov),
+            Some(Projection3D::Orthographic(mag)) if mag <= 0.0 => anyhow::bail!("magnification out of range ({})", mag),
+            _ => {}
+        }
+
+        if let Some(color) = self.background {
+            if [color.red, color.green, color.blue].iter().any(|c| !(0.0..=1.0).contains(c)) {
+                anyhow::bail!("background color out of range ({:?})", color);
+            }
+        }
+
+        let mut status = 0;
+
+        if let Some(camera) = self.camera {
+            let (cx, cy, cz) = camera.center;
+            let (dx, dy, dz) = camera.direction;
+            status = unsafe {
+                ffi::HPDF_3DView_SetCamera(dict, cx, cy, cz, dx, dy, dz, camera.distance, camera.roll)
+            };
+        }
+
+        if status == 0 {
+            status = match self.projection {
+                Some(Projection3D::Perspective(fov)) => unsafe { ffi::HPDF_3DView_SetPerspectiveProjection(dict, fov) },
+                Some(Projection3D::Orthographic(mag)) => unsafe { ffi::HPDF_3DView_SetOrthogonalProjection(dict, mag) },
+                None => 0,
+            };
+        }
+
+        if status == 0 {
+            if let Some(lighting) = self.lighting {
+                let scheme = CString::new(lighting.name())?;
+                status = unsafe { ffi::HPDF_3DView_SetLighting(dict, scheme.as_ptr()) };
+            }
+        }
+
+        if status == 0 {
+            if let Some(color) = self.background {
+                status = unsafe { ffi::HPDF_3DView_SetBackgroundColor(dict, color.red, color.green, color.blue) };
+            }
+        }
+
+        if status != 0 {
+            return Err(Error::from_num(status).into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Check the stream type tag of 3D artwork data.
+pub(crate) fn validate_data(data: &[u8]) -> anyhow::Result<()> {
+    if data.starts_with(b"U3D\0") || data.starts_with(b"PRC") {
+        return Ok(());
+    }
+
+    Err(Error::InvalidU3dData.into())
+}
@@ -0,0 +1,308 @@
+//! Vertical text layout for Chinese and Japanese.
+
+use crate::prelude::*;
+
+use std::ffi::CStr;
+use std::ops::Range;
+
+/// Orientation of a laid out run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunKind {
+    /// Full-width characters written with the vertical font.
+    Upright,
+
+    /// Short run of Latin letters and digits written horizontally in one cell. (tate-chu-yoko)
+    Horizontal,
+
+    /// Latin characters and punctuation rotated 90 degrees clockwise.
+    Rotated,
+}
+
+/// Unit of the layout with its advance, `None` is a line feed.
+type Unit = (Option<RunKind>, Range<usize>, Real);
+
+#[derive(Debug, PartialEq)]
+struct Run {
+    kind: RunKind,
+    range: Range<usize>,
+    column: usize,
+    x: Real,
+    y: Real,
+}
+
+/// Vertical text layout for Chinese and Japanese.
+///
+/// Characters are written from top to bottom in columns, and the columns flow from right to
+/// left. Full-width characters are written upright with the font of a vertical encoding, so
+/// punctuation takes the vertical forms of the CMap. Short runs of Latin letters and digits are
+/// written horizontally in one cell (tate-chu-yoko), and the other half-width characters are
+/// rotated.
+pub struct VerticalText<'doc> {
+    doc: &'doc Document,
+    font: Font<'doc>,
+    horizontal_font: Font<'doc>,
+    encoding: String,
+    font_size: Real,
+    column_gap: Real,
+    tate_chu_yoko: usize,
+}
+
+impl<'doc> VerticalText<'doc> {
+    /// Create a new layout written with the font of a vertical encoding (such as "90ms-RKSJ-V")
+    /// and the size. Latin characters are written with the same font of the horizontal encoding.
+    pub fn new(doc: &'doc Document, font: &Font, font_size: Real) -> anyhow::Result<Self> {
+        let name = font.name()?;
+        let encoding = unsafe {
+            let encoding = libharu_sys::HPDF_Font_GetEncodingName(font.handle());
+            if encoding.is_null() {
+                anyhow::bail!("HPDF_Font_GetEncodingName failed");
+            }
+
+            CStr::from_ptr(encoding).to_str()?.to_string()
+        };
+
+        if doc.find_encoder(&encoding)?.writing_mode()? != WritingMode::Vertical {
+            anyhow::bail!("font \"{}\" does not use a vertical encoding ({})", name, encoding);
+        }
+
+        let horizontal = match encoding.strip_suffix("-V") {
+            Some(base) => format!("{}-H", base),
+            None => anyhow::bail!("no horizontal encoding for {}", encoding),
+        };
+
+        Ok(Self {
+            doc,
+            font: Font::new(doc, font.handle()),
+            horizontal_font: doc.font(name, Some(&horizontal))?,
+            encoding,
+            font_size,
+            column_gap: font_size * 0.5,
+            tate_chu_yoko: 2,
+        })
+    }
+
+    /// Set the gap between columns. (default: half of the font size)
+    pub fn set_column_gap(&mut self, gap: Real) {
+        self.column_gap = gap;
+    }
+
+    /// Set the maximum number of Latin letters and digits written horizontally in one cell.
+    /// 0 disables tate-chu-yoko. (default: 2)
+    pub fn set_tate_chu_yoko(&mut self, max_chars: usize) {
+        self.tate_chu_yoko = max_chars;
+    }
+
+    /// Write the text inside the rect, and return the byte length of the written text.
+    ///
+    /// The text is converted into the vertical encoding like `show_text`, and a line feed starts
+    /// a new column. The rest of the text which does not fit in the rect can be written into
+    /// another rect. Requires the `encoding` feature; use `render_bytes` otherwise.
+    #[cfg(feature = "encoding")]
+    pub fn render<T>(&self, page: &PageDescriptionMode, rect: T, text: &str) -> anyhow::Result<usize>
+    where
+        T: Into<Rect>
+    {
+        let encoded = crate::encoder::encode_str(&self.encoding, text)?;
+        let len = self.render_bytes(page, rect, &encoded)?;
+
+        Ok(crate::encoder::source_len(&self.encoding, text, len))
+    }
+
+    /// Write the text inside the rect, and return the byte length of the written text. (bytes data)
+    pub fn render_bytes<T>(&self, page: &PageDescriptionMode, rect: T, text: &[u8]) -> anyhow::Result<usize>
+    where
+        T: Into<Rect>
+    {
+        let rect = page.map_rect(rect)?;
+        let (runs, len) = self.layout(rect, text)?;
+
+        let size = self.font_size;
+        let (ascent, descent) = unsafe {
+            let font = self.horizontal_font.handle();
+            (libharu_sys::HPDF_Font_GetAscent(font), libharu_sys::HPDF_Font_GetDescent(font))
+        };
+        /* distance from the baseline to the center of the glyphs */
+        let center = (ascent + descent) as Real * size / 2000.0;
+
+        page.run_text_mode(|page| {
+            let mut current = None;
+            for run in &runs {
+                let bytes = &text[run.range.clone()];
+                let upright = run.kind == RunKind::Upright;
+                if current != Some(upright) {
+                    page.set_font_and_size(if upright { &self.font } else { &self.horizontal_font }, size)?;
+                    current = Some(upright);
+                }
+
+                match run.kind {
+                    RunKind::Upright => {
+                        /* the origin of vertical glyphs is the center of the top edge */
+                        page.set_text_matrix(1.0, 0.0, 0.0, 1.0, run.x, run.y)?;
+                    }
+                    RunKind::Horizontal => {
+                        let width = self.advance(&self.horizontal_font, bytes);
+                        let scale = if width > size { size / width } else { 1.0 };
+                        page.set_text_matrix(scale, 0.0, 0.0, 1.0, run.x - width * scale / 2.0, run.y - size / 2.0 - center)?;
+                    }
+                    RunKind::Rotated => {
+                        page.set_text_matrix(0.0, -1.0, 1.0, 0.0, run.x - center, run.y)?;
+                    }
+                }
+
+                page.show_text_bytes(bytes)?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(len)
+    }
+
+    /// Place the runs of the text in the rect, and return them with the byte length of the placed text.
+    fn layout(&self, rect: Rect, text: &[u8]) -> anyhow::Result<(Vec<Run>, usize)> {
+        let chars: Vec<(Range<usize>, char)> = self.doc.find_encoder(&self.encoding)?.decode(text)?.collect();
+        let is_latin = |ch: char| ch.is_ascii_graphic() || ch == ' ';
+
+        /* split into units */
+        let mut units: Vec<Unit> = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let (range, ch) = chars[i].clone();
+
+            if ch == '\n' {
+                units.push((None, range, 0.0));
+                i += 1;
+            }
+            else if is_latin(ch) {
+                let end = chars[i..].iter().position(|(_, ch)| !is_latin(*ch)).map_or(chars.len(), |n| i + n);
+                let latin = &chars[i..end];
+
+                if latin.len() <= self.tate_chu_yoko && latin.iter().all(|(_, ch)| ch.is_ascii_alphanumeric()) {
+                    units.push((Some(RunKind::Horizontal), range.start..latin[latin.len() - 1].0.end, self.font_size));
+                }
+                else {
+                    for (range, _) in latin {
+                        let advance = self.advance(&self.horizontal_font, &text[range.clone()]);
+                        units.push((Some(RunKind::Rotated), range.clone(), advance));
+                    }
+                }
+
+                i = end;
+            }
+            else {
+                /* vertical fonts advance by the default vertical metrics (DW2) of 1000 */
+                units.push((Some(RunKind::Upright), range, self.font_size));
+                i += 1;
+            }
+        }
+
+        Ok(place(&units, rect, self.font_size, self.column_gap))
+    }
+
+    /// Get the horizontal advance of the text.
+    fn advance(&self, font: &Font, text: &[u8]) -> Real {
+        let width = unsafe {
+            libharu_sys::HPDF_Font_TextWidth(font.handle(), text.as_ptr() as *const _, text.len() as libharu_sys::HPDF_UINT)
+        };
+
+        width.width as Real * self.font_size / 1000.0
+    }
+}
+
+/// Place the units in columns of the rect, and return the runs with the byte length of the placed units.
+fn place(units: &[Unit], rect: Rect, font_size: Real, column_gap: Real) -> (Vec<Run>, usize) {
+    let pitch = font_size + column_gap;
+    let mut runs: Vec<Run> = Vec::new();
+    let mut column = 0;
+    let mut y = rect.top;
+    let mut len = 0;
+
+    for (kind, range, advance) in units.iter().cloned() {
+        let kind = match kind {
+            Some(kind) => kind,
+            None => {
+                column += 1;
+                y = rect.top;
+                len = range.end;
+                continue;
+            }
+        };
+
+        if y - advance < rect.bottom && y < rect.top {
+            column += 1;
+            y = rect.top;
+        }
+
+        let x = rect.right - font_size / 2.0 - column as Real * pitch;
+        if x - font_size / 2.0 < rect.left || y - advance < rect.bottom {
+            break;
+        }
+
+        match runs.last_mut() {
+            Some(last) if last.kind == kind && kind != RunKind::Horizontal && last.column == column && last.range.end == range.start => {
+                last.range.end = range.end;
+            }
+            _ => runs.push(Run { kind, range: range.clone(), column, x, y }),
+        }
+
+        y -= advance;
+        len = range.end;
+    }
+
+    (runs, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upright(range: Range<usize>) -> Unit {
+        (Some(RunKind::Upright), range, 10.0)
+    }
+
+    #[test]
+    fn place_breaks_columns() {
+        /* 3 cells of 10pt fit in a column of 35pt; columns are 10pt wide with a gap of 5pt */
+        let rect = Rect { left: 0.0, top: 35.0, right: 39.0, bottom: 0.0 };
+        let units: Vec<Unit> = (0..8).map(|i| upright(i * 2..i * 2 + 2)).collect();
+        let (runs, len) = place(&units, rect, 10.0, 5.0);
+
+        assert_eq!(runs, vec![
+            Run { kind: RunKind::Upright, range: 0..6, column: 0, x: 34.0, y: 35.0 },
+            Run { kind: RunKind::Upright, range: 6..12, column: 1, x: 19.0, y: 35.0 },
+        ]);
+        /* the third column would start at x = -1 */
+        assert_eq!(len, 12);
+
+        let rect = Rect { left: 0.0, top: 35.0, right: 40.0, bottom: 0.0 };
+        let (runs, len) = place(&units, rect, 10.0, 5.0);
+        assert_eq!(runs.len(), 3);
+        assert_eq!((runs[2].range.clone(), runs[2].x), (12..16, 5.0));
+        assert_eq!(len, 16);
+    }
+
+    #[test]
+    fn place_line_feed_and_kinds() {
+        let rect = Rect { left: 0.0, top: 100.0, right: 100.0, bottom: 0.0 };
+        let units = vec![
+            upright(0..2),
+            (Some(RunKind::Horizontal), 2..4, 10.0),
+            (Some(RunKind::Horizontal), 4..6, 10.0),
+            (Some(RunKind::Rotated), 6..7, 5.5),
+            (Some(RunKind::Rotated), 7..8, 5.5),
+            (None, 8..9, 0.0),
+            upright(9..11),
+        ];
+        let (runs, len) = place(&units, rect, 10.0, 5.0);
+
+        assert_eq!(runs, vec![
+            Run { kind: RunKind::Upright, range: 0..2, column: 0, x: 95.0, y: 100.0 },
+            Run { kind: RunKind::Horizontal, range: 2..4, column: 0, x: 95.0, y: 90.0 },
+            Run { kind: RunKind::Horizontal, range: 4..6, column: 0, x: 95.0, y: 80.0 },
+            Run { kind: RunKind::Rotated, range: 6..8, column: 0, x: 95.0, y: 70.0 },
+            Run { kind: RunKind::Upright, range: 9..11, column: 1, x: 80.0, y: 100.0 },
+        ]);
+        assert_eq!(len, 11);
+    }
+}
+
@@ -0,0 +1,275 @@
+use crate::info::{InfoType, InfoValues};
+use crate::conformance::{Conformance, ConformanceError};
+
+/// XMP metadata attached to the document catalog.
+///
+/// The generated packet contains the Dublin Core, xmp and pdf namespaces synchronized with the
+/// info dictionary when the document is saved, plus the custom properties added to it.
+#[derive(Debug, Clone, Default)]
+pub struct XmpMetadata {
+    raw: Option<String>,
+    namespaces: Vec<XmpNamespace>,
+}
+
+#[derive(Debug, Clone)]
+struct XmpNamespace {
+    prefix: String,
+    uri: String,
+    properties: Vec<(String, String)>,
+}
+
+impl XmpMetadata {
+    /// Create metadata generated from the info dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create metadata from a complete XMP packet, which is attached as is.
+    ///
+    /// The packet is not synchronized with the info dictionary. In a PDF/A document it must
+    /// contain the pdfaid part and conformance of the level, or saving fails with
+    /// `ConformanceError::MissingPdfaId`.
+    pub fn raw<T: Into<String>>(xml: T) -> Self {
+        Self { raw: Some(xml.into()), namespaces: Vec::new() }
+    }
+
+    /// Add a simple text property `prefix:name` in the custom namespace `uri`.
+    pub fn add_property(&mut self, prefix: &str, uri: &str, name: &str, value: &str) -> anyhow::Result<&mut Self> {
+        if self.raw.is_some() {
+            anyhow::bail!("properties can not be added to raw XMP metadata");
+        }
+
+        if !is_xml_name(prefix) || !is_xml_name(name) {
+            anyhow::bail!("invalid XMP property name \"{}:{}\"", prefix, name);
+        }
+
+        if ["x", "rdf", "dc", "xmp", "pdf", "pdfaid"].contains(&prefix) {
+            anyhow::bail!("namespace prefix \"{}\" is reserved", prefix);
+        }
+
+        let ns = match self.namespaces.iter_mut().position(|ns| ns.prefix == prefix) {
+            Some(i) => &mut self.namespaces[i],
+            None => {
+                self.namespaces.push(XmpNamespace { prefix: prefix.to_string(), uri: uri.to_string(), properties: Vec::new() });
+                self.namespaces.last_mut().unwrap()
+            },
+        };
+
+        if ns.uri != uri {
+            anyhow::bail!("namespace prefix \"{}\" is already bound to \"{}\"", prefix, ns.uri);
+        }
+
+        ns.properties.push((name.to_string(), value.to_string()));
+
+        Ok(self)
+    }
+
+    /// Render the XMP packet.
+    pub(crate) fn to_packet(&self, info: &InfoValues, conformance: Conformance) -> anyhow::Result<String> {
+        if let Some(raw) = &self.raw {
+            if !has_pdfaid(raw, conformance) {
+                return Err(ConformanceError::MissingPdfaId.into());
+            }
+            return Ok(raw.clone());
+        }
+
+        let mut descs = info_descriptions(info);
+        for ns in &self.namespaces {
+            let mut props = String::new();
+            for (name, value) in &ns.properties {
+                props += &format!("<{0}:{1}>{2}</{0}:{1}>\n", ns.prefix, name, escape(value));
+            }
+            descs.push(description(&ns.prefix, &ns.uri, &props));
+        }
+        descs.extend(pdfaid_description(conformance));
+
+        Ok(packet(&descs))
+    }
+}
+
+/// Check that the packet identifies the conformance level, with either elements or attributes.
+fn has_pdfaid(xml: &str, conformance: Conformance) -> bool {
+    let (part, level) = match conformance {
+        Conformance::None => return true,
+        Conformance::PdfA1B => ("1", "B"),
+    };
+
+    let has_property = |name: &str, value: &str| {
+        [format!("<pdfaid:{0}>{1}</pdfaid:{0}>", name, value), format!("pdfaid:{}=\"{}\"", name, value), format!("pdfaid:{}='{}'", name, value)]
+            .iter()
+            .any(|s| xml.contains(s.as_str()))
+    };
+
+    xml.contains("http://www.aiim.org/pdfa/ns/id/") && has_property("part", part) && has_property("conformance", level)
+}
+
+/// Check the text is usable as an XML name without a colon.
+fn is_xml_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {},
+        _ => return false,
+    }
+    chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// Escape the text for XML character data and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    let mut s = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => s.push_str("&amp;"),
+            '<' => s.push_str("&lt;"),
+            '>' => s.push_str("&gt;"),
+            '"' => s.push_str("&quot;"),
+            '\'' => s.push_str("&apos;"),
+            c => s.push(c),
+        }
+    }
+    s
+}
+
+/// Wrap rdf:Description elements into a complete XMP packet.
+pub(crate) fn packet(descriptions: &[String]) -> String {
+    let mut s = String::from("<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n");
+    s += "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n";
+    s += "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n";
+    for desc in descriptions {
+        s += desc;
+    }
+    s += "</rdf:RDF>\n";
+    s += "</x:xmpmeta>\n";
+
+    /* padding allows in-place editing of the packet */
+    for _ in 0..20 {
+        s += &" ".repeat(99);
+        s.push('\n');
+    }
+    s += "<?xpacket end=\"w\"?>";
+    s
+}
+
+/// rdf:Description element of one namespace.
+pub(crate) fn description(prefix: &str, uri: &str, properties: &str) -> String {
+    format!("<rdf:Description rdf:about=\"\" xmlns:{}=\"{}\">\n{}</rdf:Description>\n", prefix, escape(uri), properties)
+}
+
+/// Descriptions of the dc, xmp and pdf namespaces synchronized with the info dictionary.
+pub(crate) fn info_descriptions(info: &InfoValues) -> Vec<String> {
+    let text = |t: InfoType| info.text.get(&t).map(|s| escape(s));
+    let mut descs = Vec::new();
+
+    let mut dc = String::from("<dc:format>application/pdf</dc:format>\n");
+    if let Some(v) = text(InfoType::Title) {
+        dc += &format!("<dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:title>\n", v);
+    }
+    if let Some(v) = text(InfoType::Author) {
+        dc += &format!("<dc:creator><rdf:Seq><rdf:li>{}</rdf:li></rdf:Seq></dc:creator>\n", v);
+    }
+    if let Some(v) = text(InfoType::Subject) {
+        dc += &format!("<dc:description><rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt></dc:description>\n", v);
+    }
+    descs.push(description("dc", "http://purl.org/dc/elements/1.1/", &dc));
+
+    let mut xmp = String::new();
+    if let Some(v) = text(InfoType::Creator) {
+        xmp += &format!("<xmp:CreatorTool>{}</xmp:CreatorTool>\n", v);
+    }
+    if let Some(v) = info.dates.get(&InfoType::CreationDate) {
+        xmp += &format!("<xmp:CreateDate>{}</xmp:CreateDate>\n", v.to_xmp());
+    }
+    if let Some(v) = info.dates.get(&InfoType::ModDate) {
+        xmp += &format!("<xmp:ModifyDate>{}</xmp:ModifyDate>\n", v.to_xmp());
+    }
+    if !xmp.is_empty() {
+        descs.push(description("xmp", "http://ns.adobe.com/xap/1.0/", &xmp));
+    }
+
+    let mut pdf = String::new();
+    if let Some(v) = text(InfoType::Keywords) {
+        pdf += &format!("<pdf:Keywords>{}</pdf:Keywords>\n", v);
+    }
+    if let Some(v) = text(InfoType::Producer) {
+        pdf += &format!("<pdf:Producer>{}</pdf:Producer>\n", v);
+    }
+    if !pdf.is_empty() {
+        descs.push(description("pdf", "http://ns.adobe.com/pdf/1.3/", &pdf));
+    }
+
+    descs
+}
+
+/// Description of the PDF/A identification schema.
+pub(crate) fn pdfaid_description(conformance: Conformance) -> Option<String> {
+    match conformance {
+        Conformance::None => None,
+        Conformance::PdfA1B => Some(description("pdfaid", "http://www.aiim.org/pdfa/ns/id/",
+            "<pdfaid:part>1</pdfaid:part>\n<pdfaid:conformance>B</pdfaid:conformance>\n")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_markup_characters() {
+        assert_eq!(escape("a < b && c > \"d\" 'e'"), "a &lt; b &amp;&amp; c &gt; &quot;d&quot; &apos;e&apos;");
+        assert_eq!(escape("日本語"), "日本語");
+    }
+
+    #[test]
+    fn packet_wraps_descriptions() {
+        let packet = packet(&["<first/>\n".to_string(), "<second/>\n".to_string()]);
+
+        assert!(packet.starts_with("<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n<x:xmpmeta"));
+        assert!(packet.ends_with("\n<?xpacket end=\"w\"?>"));
+        assert!(packet.contains("<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n<first/>\n<second/>\n</rdf:RDF>\n</x:xmpmeta>\n"));
+        /* 20 lines of padding */
+        assert_eq!(packet.matches(&format!("{}\n", " ".repeat(99))).count(), 20);
+    }
+
+    #[test]
+    fn to_packet_escapes_info_and_properties() {
+        let mut info = InfoValues::default();
+        info.text.insert(InfoType::Title, "Fish & Chips".to_string());
+
+        let mut xmp = XmpMetadata::new();
+        xmp.add_property("ex", "http://example.com/ns?a=1&b=2", "note", "<b>").unwrap();
+        let packet = xmp.to_packet(&info, Conformance::None).unwrap();
+
+        assert!(packet.contains("<rdf:li xml:lang=\"x-default\">Fish &amp; Chips</rdf:li>"));
+        assert!(packet.contains("xmlns:ex=\"http://example.com/ns?a=1&amp;b=2\">\n<ex:note>&lt;b&gt;</ex:note>\n"));
+        assert!(!packet.contains("pdfaid"));
+
+        let packet = xmp.to_packet(&info, Conformance::PdfA1B).unwrap();
+        assert!(packet.contains("<pdfaid:part>1</pdfaid:part>\n<pdfaid:conformance>B</pdfaid:conformance>"));
+    }
+
+    #[test]
+    fn add_property_rejects_invalid_names() {
+        let mut xmp = XmpMetadata::new();
+        assert!(xmp.add_property("dc", "http://purl.org/dc/elements/1.1/", "title", "x").is_err());
+        assert!(xmp.add_property("1ex", "http://example.com/", "note", "x").is_err());
+        assert!(xmp.add_property("ex", "http://example.com/", "a:b", "x").is_err());
+
+        xmp.add_property("ex", "http://example.com/", "note", "x").unwrap();
+        assert!(xmp.add_property("ex", "http://example.com/other/", "note", "x").is_err());
+    }
+
+    #[test]
+    fn raw_packet_requires_pdfaid_in_pdfa() {
+        let info = InfoValues::default();
+        let plain = XmpMetadata::raw(packet(&[description("dc", "http://purl.org/dc/elements/1.1/", "")]));
+        assert!(plain.to_packet(&info, Conformance::None).is_ok());
+
+        let err = plain.to_packet(&info, Conformance::PdfA1B).unwrap_err();
+        assert_eq!(err.downcast_ref::<ConformanceError>(), Some(&ConformanceError::MissingPdfaId));
+
+        let elements = XmpMetadata::raw(packet(&[pdfaid_description(Conformance::PdfA1B).unwrap()]));
+        assert!(elements.to_packet(&info, Conformance::PdfA1B).is_ok());
+
+        let attributes = XmpMetadata::raw(packet(&["<rdf:Description rdf:about=\"\" xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\" pdfaid:part=\"1\" pdfaid:conformance=\"B\"/>\n".to_string()]));
+        assert!(attributes.to_packet(&info, Conformance::PdfA1B).is_ok());
+    }
+}
@@ -0,0 +1,31 @@
+use crate::document::Document;
+
+/// XObject handle type.
+pub struct XObject<'a> {
+    doc: &'a Document,
+    xobj: libharu_sys::HPDF_XObject,
+    width: crate::Real,
+    height: crate::Real,
+}
+
+impl<'a> XObject<'a> {
+    pub(crate) fn new(doc: &'a Document, xobj: libharu_sys::HPDF_XObject, width: crate::Real, height: crate::Real) -> Self {
+        Self { doc, xobj, width, height }
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> libharu_sys::HPDF_XObject {
+        self.doc.enter_memory();
+        self.xobj
+    }
+
+    /// Get the width of the bounding box of the form XObject.
+    pub fn width(&self) -> crate::Real {
+        self.width
+    }
+
+    /// Get the height of the bounding box of the form XObject.
+    pub fn height(&self) -> crate::Real {
+        self.height
+    }
+}
@@ -0,0 +1,152 @@
+extern crate libharu;
+extern crate anyhow;
+
+use libharu::prelude::*;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn assert_send<T: Send>() {}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("libharu-concurrent-{}-{}.pdf", std::process::id(), name))
+}
+
+/// Draw the same pages for every document, so that the outputs can be compared.
+fn draw_pages(doc: &Document, title: &str) -> anyhow::Result<()> {
+    doc.set_info_attr(InfoType::Title, title)?;
+
+    let font = doc.font("Helvetica", None)?;
+    for i in 0..5 {
+        let page = doc.add_page()?;
+        let page = PageDescriptionMode::new(&page);
+
+        page.set_font_and_size(&font, 24.0)?;
+        page.run_text_mode(|page| {
+            page.text_out((50.0, 700.0), &format!("{} page {}", title, i))
+        })?;
+
+        page.run_path_mode(|page| {
+            page.rectangle((50.0, 50.0), 100.0 + i as Real, 100.0)?;
+            page.stroke()
+        })?;
+    }
+
+    Ok(())
+}
+
+fn build(title: &str, name: &str) -> anyhow::Result<Vec<u8>> {
+    let doc = Document::new(|err| panic!("unexpected error {:?}", err))?;
+    draw_pages(&doc, title)?;
+
+    let path = temp_path(name);
+    doc.save_to_file(path.to_str().unwrap())?;
+
+    let data = std::fs::read(&path)?;
+    std::fs::remove_file(&path)?;
+
+    Ok(data)
+}
+
+#[test]
+fn document_is_send() {
+    assert_send::<Document>();
+}
+
+#[test]
+fn concurrent_documents_do_not_interfere() {
+    let expected: Vec<Vec<u8>> = (0..8)
+        .map(|i| build(&format!("doc {}", i), &format!("expected-{}", i)).unwrap())
+        .collect();
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| std::thread::spawn(move || build(&format!("doc {}", i), &format!("thread-{}", i)).unwrap()))
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert_eq!(handle.join().unwrap(), expected[i]);
+    }
+}
+
+#[test]
+fn document_moves_between_threads() {
+    let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+    doc.add_page().unwrap();
+
+    let doc = std::thread::spawn(move || {
+        doc.add_page().unwrap();
+        doc
+    }).join().unwrap();
+
+    let path = temp_path("moved");
+    doc.save_to_file(path.to_str().unwrap()).unwrap();
+
+    let data = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(data.starts_with(b"%PDF-"));
+    assert!(data.windows(8).any(|w| w == b"/Count 2"));
+}
+
+#[test]
+fn error_handlers_are_per_document() {
+    let counters: Vec<Arc<AtomicUsize>> = (0..4).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+
+    let handles: Vec<_> = counters.iter().cloned().enumerate()
+        .map(|(i, counter)| std::thread::spawn(move || {
+            let doc = Document::new(move |_| { counter.fetch_add(1, Ordering::SeqCst); }).unwrap();
+
+            /* only the odd documents raise an error */
+            if i % 2 == 1 {
+                assert!(doc.font("NoSuchFont", None).is_err());
+            }
+        }))
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    for (i, counter) in counters.iter().enumerate() {
+        assert_eq!(counter.load(Ordering::SeqCst), i % 2, "document {}", i);
+    }
+}
+
+#[test]
+fn accounted_documents_are_not_limited_in_number() {
+    let docs: Vec<Document> = (0..40)
+        .map(|_| Document::builder().track_memory().build().unwrap())
+        .collect();
+
+    /* interleave the documents, so that each allocation is charged to the right one */
+    for round in 0..10 {
+        for (i, doc) in docs.iter().enumerate() {
+            if i % 2 == 0 || round == 0 {
+                doc.add_page().unwrap();
+            }
+        }
+    }
+
+    for pair in docs.chunks(2) {
+        let (busy, idle) = (pair[0].memory_stats().unwrap(), pair[1].memory_stats().unwrap());
+        assert!(busy.current > idle.current, "{:?} {:?}", busy, idle);
+        assert!(idle.current > 0);
+    }
+}
+
+#[test]
+fn accounted_documents_on_threads_are_charged_separately() {
+    let handles: Vec<_> = (0..8)
+        .map(|i| std::thread::spawn(move || {
+            let doc = Document::builder().memory_limit(4 * 1024 * 1024).build().unwrap();
+            draw_pages(&doc, &format!("doc {}", i)).unwrap();
+            doc.memory_stats().unwrap()
+        }))
+        .collect();
+
+    let stats: Vec<MemoryStats> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    for stat in &stats {
+        assert!(stat.current > 0 && stat.current <= stat.peak);
+        assert_eq!(stat.current, stats[0].current);
+    }
+}
@@ -0,0 +1,163 @@
+extern crate libharu;
+extern crate anyhow;
+
+use libharu::prelude::*;
+
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("libharu-recovery-{}-{}", std::process::id(), name))
+}
+
+fn write_temp(name: &str, data: &[u8]) -> PathBuf {
+    let path = temp_path(name);
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+/// Check that the document is not in error state, and that pages can still be drawn and saved.
+fn assert_usable(doc: &Document) {
+    assert!(doc.last_error().is_none(), "{:?}", doc.last_error());
+    assert!(doc.has_doc());
+
+    let font = doc.font("Helvetica", None).unwrap();
+    let page = doc.add_page().unwrap();
+    let page = PageDescriptionMode::new(&page);
+    page.set_font_and_size(&font, 12.0).unwrap();
+    page.run_text_mode(|page| page.text_out((50.0, 50.0), "still usable")).unwrap();
+
+    let data = doc.save_to_bytes().unwrap();
+    assert!(data.starts_with(b"%PDF-"));
+    assert!(data.ends_with(b"%%EOF\n"));
+}
+
+fn error_of(err: &anyhow::Error) -> &Error {
+    &err.downcast_ref::<ErrorDetail>().expect("libharu error").error
+}
+
+#[test]
+fn missing_font_file() {
+    let doc = Document::new(|_| {}).unwrap();
+
+    let err = doc.load_ttf_font(temp_path("missing.ttf").to_str().unwrap(), true).unwrap_err();
+    assert!(matches!(error_of(&err), Error::FileOpenError));
+
+    assert_usable(&doc);
+}
+
+#[test]
+fn corrupt_font_file() {
+    let doc = Document::new(|_| {}).unwrap();
+    /* truncated offset table */
+    let path = write_temp("corrupt.ttf", &[0x00, 0x01, 0x00, 0x00]);
+
+    assert!(doc.load_ttf_font(path.to_str().unwrap(), true).is_err());
+    std::fs::remove_file(&path).unwrap();
+
+    assert_usable(&doc);
+}
+
+#[test]
+fn corrupt_png_image() {
+    let doc = Document::new(|_| {}).unwrap();
+    let path = write_temp("corrupt.png", b"\x89PNG\r\n\x1a\nnot really a png");
+
+    assert!(doc.load_png_image(path.to_str().unwrap()).is_err());
+    std::fs::remove_file(&path).unwrap();
+
+    assert_usable(&doc);
+}
+
+#[test]
+fn unknown_font_and_encoding_names() {
+    let doc = Document::new(|_| {}).unwrap();
+
+    let err = doc.font("NoSuchFont", None).err().unwrap();
+    assert!(matches!(error_of(&err), Error::InvalidFontName));
+
+    let err = doc.font("Helvetica", Some("NoSuchEncoding")).err().unwrap();
+    assert!(matches!(error_of(&err), Error::InvalidEncodingName));
+
+    assert!(doc.find_encoder("NoSuchEncoding").is_err());
+    assert!(doc.set_current_encoder("NoSuchEncoding").is_err());
+
+    assert_usable(&doc);
+}
+
+#[test]
+fn page_errors_need_recover() {
+    let doc = Document::new(|_| {}).unwrap();
+    let page = doc.add_page().unwrap();
+
+    /* no graphics state to restore */
+    assert!(page.grestore().is_err());
+    assert!(matches!(doc.last_error().unwrap().error, Error::PageCannotRestoreGstate));
+
+    doc.recover().unwrap();
+    assert_usable(&doc);
+
+    /* negative line width */
+    let page = doc.add_page().unwrap();
+    let page = PageDescriptionMode::new(&page);
+    assert!(page.set_line_width(-1.0).is_err());
+
+    doc.recover().unwrap();
+    assert_usable(&doc);
+}
+
+#[test]
+fn error_state_persists_without_recover() {
+    let doc = Document::new(|_| {}).unwrap();
+    let page = doc.add_page().unwrap();
+
+    assert!(page.grestore().is_err());
+    assert!(doc.add_page().is_err());
+
+    doc.recover().unwrap();
+    assert_usable(&doc);
+}
+
+#[test]
+fn memory_exhaustion_is_not_recoverable() {
+    let mut doc = Document::builder().memory_limit(64 * 1024).build().unwrap();
+
+    let mut failed = false;
+    for _ in 0..1000 {
+        if doc.add_page().is_err() {
+            failed = true;
+            break;
+        }
+    }
+    assert!(failed);
+
+    let err = doc.recover().unwrap_err();
+    assert!(matches!(error_of(&err), Error::FailedToAllocMem));
+
+    /* discarding the document releases the memory */
+    doc.reset().unwrap();
+    doc.recover().unwrap();
+    assert_usable(&doc);
+}
+
+#[test]
+fn name_out_of_range_is_recoverable() {
+    let err = Error::NameOutOfRange;
+    assert!(err.is_recoverable());
+    assert_eq!(err.message(), "The name is empty or exceeds the length limit (127 bytes).");
+    assert!(err.to_string().ends_with(err.message()));
+}
+
+#[test]
+fn rejected_slide_show() {
+    let doc = Document::new(|err| panic!("unexpected error {:?}", err)).unwrap();
+    let page = doc.add_page().unwrap();
+
+    let err = page.set_slide_show(Transition::Dissolve, -1.0, 1.0).unwrap_err();
+    assert!(matches!(err.downcast_ref::<Error>(), Some(Error::PageInvalidDisplayTime)));
+    let err = page.set_slide_show(Transition::Dissolve, 5.0, Real::NAN).unwrap_err();
+    assert!(matches!(err.downcast_ref::<Error>(), Some(Error::PageInvalidTransitionTime)));
+
+    page.set_slide_show(Transition::Dissolve, 5.0, 1.0).unwrap();
+
+    assert_usable(&doc);
+}